@@ -0,0 +1,437 @@
+//! Accounts each instruction expects, checked before the handler runs.
+//!
+//! `load` takes the accounts in the order `PokerInstruction` documents them
+//! and checks everything that does not depend on the table: signers,
+//! writability, owners and program ids. `validate` then checks the accounts
+//! the stored `GameState` pins down, such as the vault, the mint and the
+//! authority, once the handler has deserialized it.
+
+use crate::{custody::Custody, error::PokerError, token, GameState};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use std::slice::Iter;
+
+fn signer(account: &AccountInfo) -> ProgramResult {
+    if !account.is_signer {
+        msg!("{} must sign", account.key);
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+fn writable(account: &AccountInfo) -> ProgramResult {
+    if !account.is_writable {
+        msg!("{} must be writable", account.key);
+        return Err(PokerError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+fn program(account: &AccountInfo, id: &Pubkey) -> ProgramResult {
+    if account.key != id {
+        msg!("Expected program {}, got {}", id, account.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// SPL Token or Token-2022
+fn token_program(account: &AccountInfo) -> ProgramResult {
+    if !token::is_token_program(account.key) {
+        msg!("{} is not a token program", account.key);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// A token program, or the system program for native tables
+fn custody_program(account: &AccountInfo) -> ProgramResult {
+    if *account.key == system_program::id() {
+        return Ok(());
+    }
+    token_program(account)
+}
+
+fn rent_sysvar(account: &AccountInfo) -> ProgramResult {
+    if *account.key != sysvar::rent::id() {
+        msg!("Expected the rent sysvar, got {}", account.key);
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// The game state must belong to this program and be writable
+fn game_state(program_id: &Pubkey, account: &AccountInfo) -> ProgramResult {
+    if account.owner != program_id {
+        msg!("Game state account is owned by {}", account.owner);
+        return Err(PokerError::InvalidGameAccount.into());
+    }
+    writable(account)
+}
+
+/// A token account holding the table's mint
+fn holds_mint(account: &AccountInfo, state: &GameState) -> bool {
+    account.owner == &state.token_program
+        && token::unpack_account(account).is_ok_and(|token| token.mint == state.mint)
+}
+
+/// Where `player`'s funds move from and to: a token account holding the
+/// table's mint, or the player's own wallet on native tables
+fn funds_account(account: &AccountInfo, player: &AccountInfo, state: &GameState) -> ProgramResult {
+    let is_funds = match state.custody {
+        Custody::Token => holds_mint(account, state),
+        Custody::Lamports => account.key == player.key,
+    };
+    if !is_funds {
+        msg!("{} cannot hold {} for {}", account.key, state.mint, player.key);
+        return Err(PokerError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+/// The table's mint and the token program that owns it
+fn mint_and_program(mint: &AccountInfo, token_program: &AccountInfo, state: &GameState) -> ProgramResult {
+    if *mint.key != state.mint {
+        msg!("{} is not the table's mint", mint.key);
+        return Err(PokerError::InvalidMint.into());
+    }
+    if *token_program.key != state.token_program {
+        msg!("Table's mint is owned by {}", state.token_program);
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// The table's vault
+fn vault(account: &AccountInfo, state: &GameState) -> ProgramResult {
+    if *account.key != state.pot_token_account {
+        msg!("Invalid pot token account");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+    Ok(())
+}
+
+/// The table's authority
+fn table_authority(account: &AccountInfo, state: &GameState) -> ProgramResult {
+    if *account.key != state.authority {
+        msg!("{} is not the table authority", account.key);
+        return Err(PokerError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// `InitializeGame`
+#[derive(Clone, Copy)]
+pub struct InitializeGameAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub pot_token_account: &'a AccountInfo<'info>,
+    pub pot_authority: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_sysvar: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> InitializeGameAccounts<'a, 'info> {
+    pub fn load(accounts_iter: &mut Iter<'a, AccountInfo<'info>>) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            authority: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            mint: next_account_info(accounts_iter)?,
+            pot_token_account: next_account_info(accounts_iter)?,
+            pot_authority: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+            associated_token_program: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            rent_sysvar: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.authority)?;
+        writable(accounts.authority)?;
+        writable(accounts.game_state)?;
+        writable(accounts.pot_token_account)?;
+        token_program(accounts.token_program)?;
+        program(accounts.associated_token_program, &spl_associated_token_account::id())?;
+        program(accounts.system_program, &system_program::id())?;
+        rent_sysvar(accounts.rent_sysvar)?;
+        if accounts.mint.owner != accounts.token_program.key {
+            msg!("{} is not a mint of {}", accounts.mint.key, accounts.token_program.key);
+            return Err(PokerError::InvalidMint.into());
+        }
+
+        Ok(accounts)
+    }
+}
+
+/// `InitializeNativeGame`
+#[derive(Clone, Copy)]
+pub struct InitializeNativeGameAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub escrow: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+    pub rent_sysvar: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> InitializeNativeGameAccounts<'a, 'info> {
+    pub fn load(accounts_iter: &mut Iter<'a, AccountInfo<'info>>) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            authority: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            escrow: next_account_info(accounts_iter)?,
+            system_program: next_account_info(accounts_iter)?,
+            rent_sysvar: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.authority)?;
+        writable(accounts.authority)?;
+        writable(accounts.game_state)?;
+        writable(accounts.escrow)?;
+        program(accounts.system_program, &system_program::id())?;
+        rent_sysvar(accounts.rent_sysvar)?;
+
+        Ok(accounts)
+    }
+}
+
+/// `BuyIn`
+#[derive(Clone, Copy)]
+pub struct BuyInAccounts<'a, 'info> {
+    pub player: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub player_token_account: &'a AccountInfo<'info>,
+    pub pot_token_account: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> BuyInAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            player: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            player_token_account: next_account_info(accounts_iter)?,
+            pot_token_account: next_account_info(accounts_iter)?,
+            mint: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.player)?;
+        game_state(program_id, accounts.game_state)?;
+        writable(accounts.player_token_account)?;
+        writable(accounts.pot_token_account)?;
+        custody_program(accounts.token_program)?;
+
+        Ok(accounts)
+    }
+
+    pub fn validate(&self, state: &GameState) -> ProgramResult {
+        vault(self.pot_token_account, state)?;
+        mint_and_program(self.mint, self.token_program, state)?;
+        funds_account(self.player_token_account, self.player, state)
+    }
+}
+
+/// Instructions signed by a player, or by anyone for `TimeoutAction`, that
+/// only touch the game state
+#[derive(Clone, Copy)]
+pub struct PlayerAccounts<'a, 'info> {
+    pub player: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> PlayerAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            player: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.player)?;
+        game_state(program_id, accounts.game_state)?;
+
+        Ok(accounts)
+    }
+}
+
+/// Instructions signed by the table authority that only touch the game
+/// state, such as `StartRound` and `SetRandomnessOracle`
+#[derive(Clone, Copy)]
+pub struct AuthorityAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> AuthorityAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            authority: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.authority)?;
+        game_state(program_id, accounts.game_state)?;
+
+        Ok(accounts)
+    }
+
+    pub fn validate(&self, state: &GameState) -> ProgramResult {
+        table_authority(self.authority, state)
+    }
+}
+
+/// `EndRound`
+#[derive(Clone, Copy)]
+pub struct EndRoundAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub pot_token_account: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> EndRoundAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            authority: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            pot_token_account: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.authority)?;
+        game_state(program_id, accounts.game_state)?;
+
+        Ok(accounts)
+    }
+
+    pub fn validate(&self, state: &GameState) -> ProgramResult {
+        table_authority(self.authority, state)?;
+        vault(self.pot_token_account, state)
+    }
+}
+
+/// `CashOut`
+#[derive(Clone, Copy)]
+pub struct CashOutAccounts<'a, 'info> {
+    pub player: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub pot_token_account: &'a AccountInfo<'info>,
+    pub player_token_account: &'a AccountInfo<'info>,
+    pub pot_authority: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> CashOutAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            player: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            pot_token_account: next_account_info(accounts_iter)?,
+            player_token_account: next_account_info(accounts_iter)?,
+            pot_authority: next_account_info(accounts_iter)?,
+            mint: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.player)?;
+        game_state(program_id, accounts.game_state)?;
+        writable(accounts.pot_token_account)?;
+        writable(accounts.player_token_account)?;
+        custody_program(accounts.token_program)?;
+
+        Ok(accounts)
+    }
+
+    pub fn validate(&self, state: &GameState) -> ProgramResult {
+        vault(self.pot_token_account, state)?;
+        if *self.pot_authority.key != state.pot_authority {
+            msg!("Invalid pot authority");
+            return Err(PokerError::InvalidPotAccount.into());
+        }
+        mint_and_program(self.mint, self.token_program, state)?;
+        funds_account(self.player_token_account, self.player, state)
+    }
+}
+
+/// `WithdrawRake`
+#[derive(Clone, Copy)]
+pub struct WithdrawRakeAccounts<'a, 'info> {
+    pub authority: &'a AccountInfo<'info>,
+    pub game_state: &'a AccountInfo<'info>,
+    pub pot_token_account: &'a AccountInfo<'info>,
+    pub treasury: &'a AccountInfo<'info>,
+    pub pot_authority: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> WithdrawRakeAccounts<'a, 'info> {
+    pub fn load(
+        program_id: &Pubkey,
+        accounts_iter: &mut Iter<'a, AccountInfo<'info>>,
+    ) -> Result<Self, ProgramError> {
+        let accounts = Self {
+            authority: next_account_info(accounts_iter)?,
+            game_state: next_account_info(accounts_iter)?,
+            pot_token_account: next_account_info(accounts_iter)?,
+            treasury: next_account_info(accounts_iter)?,
+            pot_authority: next_account_info(accounts_iter)?,
+            mint: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+        };
+
+        signer(accounts.authority)?;
+        game_state(program_id, accounts.game_state)?;
+        writable(accounts.pot_token_account)?;
+        writable(accounts.treasury)?;
+        custody_program(accounts.token_program)?;
+
+        Ok(accounts)
+    }
+
+    pub fn validate(&self, state: &GameState) -> ProgramResult {
+        table_authority(self.authority, state)?;
+        vault(self.pot_token_account, state)?;
+        if *self.pot_authority.key != state.pot_authority {
+            msg!("Invalid pot authority");
+            return Err(PokerError::InvalidPotAccount.into());
+        }
+        mint_and_program(self.mint, self.token_program, state)?;
+
+        // Native rake goes to a wallet
+        let is_treasury = match state.custody {
+            Custody::Token => holds_mint(self.treasury, state),
+            Custody::Lamports => {
+                self.treasury.owner == &system_program::id()
+                    && *self.treasury.key != state.pot_token_account
+            }
+        };
+        if !is_treasury {
+            msg!("{} cannot receive {}", self.treasury.key, state.mint);
+            return Err(PokerError::InvalidTokenAccount.into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//! Where a table keeps the funds behind its chips.
+//!
+//! Token tables hold their mint in the pot authority's associated token
+//! account. Native tables hold lamports in the pot authority PDA itself,
+//! which the program owns, so payouts move lamports directly instead of
+//! going through a CPI. Game logic only ever sees chips; moving funds in and
+//! out of the vault and reading what it holds go through `Custody`.
+
+use crate::{error::PokerError, token};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    system_instruction,
+    sysvar::{rent::Rent, Sysvar},
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Custody {
+    /// SPL Token or Token-2022 in the pot authority's token account
+    Token,
+    /// Native SOL in the pot authority PDA
+    Lamports,
+}
+
+/// Accounts that move funds between a player and the vault
+#[derive(Clone, Copy)]
+pub struct VaultTransfer<'a, 'info> {
+    pub vault: &'a AccountInfo<'info>,
+    pub funds: &'a AccountInfo<'info>, // Player's token account, or their wallet on native tables
+    pub mint: &'a AccountInfo<'info>,
+    pub program: &'a AccountInfo<'info>, // Token program, or the system program on native tables
+    pub decimals: u8,
+}
+
+impl Custody {
+    /// What `vault` holds for chips. A native vault's rent-exempt reserve
+    /// is not part of it. Anyone can send funds to a vault, so this can be
+    /// more than the chips in play but never less.
+    pub fn balance(self, vault: &AccountInfo) -> Result<u64, ProgramError> {
+        match self {
+            Custody::Token => Ok(token::unpack_account(vault)?.amount),
+            Custody::Lamports => {
+                let reserve = Rent::get()?.minimum_balance(vault.data_len());
+                Ok(vault.lamports().saturating_sub(reserve))
+            }
+        }
+    }
+
+    /// Move `amount` from the player's funds into the vault, signed by
+    /// `owner`
+    pub fn deposit<'info>(
+        self,
+        transfer: &VaultTransfer<'_, 'info>,
+        owner: &AccountInfo<'info>,
+        amount: u64,
+    ) -> ProgramResult {
+        match self {
+            Custody::Token => invoke(
+                &spl_token_2022::instruction::transfer_checked(
+                    transfer.program.key,
+                    transfer.funds.key,
+                    transfer.mint.key,
+                    transfer.vault.key,
+                    owner.key,
+                    &[],
+                    amount,
+                    transfer.decimals,
+                )?,
+                &[
+                    transfer.funds.clone(),
+                    transfer.mint.clone(),
+                    transfer.vault.clone(),
+                    owner.clone(),
+                    transfer.program.clone(),
+                ],
+            ),
+            Custody::Lamports => invoke(
+                &system_instruction::transfer(owner.key, transfer.vault.key, amount),
+                &[owner.clone(), transfer.vault.clone(), transfer.program.clone()],
+            ),
+        }
+    }
+
+    /// Move `amount` out of the vault to the player's funds. Token vaults
+    /// need the pot authority's signature; the program owns native vaults
+    /// and debits them itself.
+    pub fn withdraw<'info>(
+        self,
+        transfer: &VaultTransfer<'_, 'info>,
+        pot_authority: &AccountInfo<'info>,
+        signer_seeds: &[&[u8]],
+        amount: u64,
+    ) -> ProgramResult {
+        match self {
+            Custody::Token => invoke_signed(
+                &spl_token_2022::instruction::transfer_checked(
+                    transfer.program.key,
+                    transfer.vault.key,
+                    transfer.mint.key,
+                    transfer.funds.key,
+                    pot_authority.key,
+                    &[],
+                    amount,
+                    transfer.decimals,
+                )?,
+                &[
+                    transfer.vault.clone(),
+                    transfer.mint.clone(),
+                    transfer.funds.clone(),
+                    pot_authority.clone(),
+                    transfer.program.clone(),
+                ],
+                &[signer_seeds],
+            ),
+            Custody::Lamports => {
+                let vault_lamports = transfer
+                    .vault
+                    .lamports()
+                    .checked_sub(amount)
+                    .ok_or(PokerError::Overflow)?;
+                let funds_lamports = transfer
+                    .funds
+                    .lamports()
+                    .checked_add(amount)
+                    .ok_or(PokerError::Overflow)?;
+                **transfer.vault.try_borrow_mut_lamports()? = vault_lamports;
+                **transfer.funds.try_borrow_mut_lamports()? = funds_lamports;
+                Ok(())
+            }
+        }
+    }
+}
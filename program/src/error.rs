@@ -0,0 +1,275 @@
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+/// Errors returned by the poker program.
+///
+/// Discriminants are part of the on-chain ABI: they surface in transaction
+/// failures as `Custom(code)`, so existing values must never be renumbered.
+/// Append new variants at the end.
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum PokerError {
+    /// Instruction data could not be decoded
+    #[error("Invalid instruction")]
+    InvalidInstruction = 0,
+
+    /// Every seat at the table is taken
+    #[error("Game is full")]
+    GameFull = 1,
+
+    /// Buy-in is outside the table's allowed range
+    #[error("Buy-in amount is outside the allowed range")]
+    InvalidBuyIn = 2,
+
+    /// Signer is not seated at this table
+    #[error("Player not found at this table")]
+    PlayerNotFound = 3,
+
+    /// Signer is not the table authority
+    #[error("Not authorized")]
+    Unauthorized = 4,
+
+    /// Pot account does not match the table's pot PDA
+    #[error("Invalid pot token account")]
+    InvalidPotAccount = 5,
+
+    /// Player does not have enough chips for the action
+    #[error("Insufficient chips")]
+    InsufficientChips = 6,
+
+    /// Raise is below the table minimum
+    #[error("Raise amount is below the minimum")]
+    RaiseTooSmall = 7,
+
+    /// A hand cannot start with fewer than two players
+    #[error("Need at least 2 players to start")]
+    NotEnoughPlayers = 8,
+
+    /// Action is only allowed between hands
+    #[error("A hand is in progress")]
+    HandInProgress = 9,
+
+    /// Winners listed for a pot are empty, repeated or not eligible for it
+    #[error("Invalid winners for a pot")]
+    InvalidWinner = 10,
+
+    /// Player has no chips to cash out
+    #[error("No chips to cash out")]
+    NothingToCashOut = 11,
+
+    /// Chip arithmetic overflowed
+    #[error("Arithmetic overflow")]
+    Overflow = 12,
+
+    /// Vault holds less than the chips it backs
+    #[error("Pot vault balance is short of the chips in play")]
+    VaultBalanceMismatch = 13,
+
+    /// Another seat holds the action
+    #[error("Not your turn")]
+    NotYourTurn = 14,
+
+    /// Player has already folded this hand
+    #[error("Player has folded")]
+    PlayerFolded = 15,
+
+    /// No betting street is in progress
+    #[error("No active betting round")]
+    NoActiveBettingRound = 16,
+
+    /// Hand has not reached showdown yet
+    #[error("Hand is not complete")]
+    HandNotComplete = 17,
+
+    /// Check attempted while facing a bet
+    #[error("Cannot check facing a bet")]
+    CannotCheck = 18,
+
+    /// Call attempted with no bet to match
+    #[error("Nothing to call")]
+    NothingToCall = 19,
+
+    /// Bet attempted after the street was already opened
+    #[error("A bet has already been made; raise instead")]
+    BetAlreadyMade = 20,
+
+    /// Raise attempted before anyone has bet
+    #[error("No bet to raise; bet instead")]
+    NoBetToRaise = 21,
+
+    /// Opening bet is below the table minimum
+    #[error("Bet amount is below the minimum")]
+    BetTooSmall = 22,
+
+    /// Player is all-in and has nothing left to act with
+    #[error("Player is all-in")]
+    PlayerAllIn = 23,
+
+    /// Settlement did not name winners for exactly the pots in play
+    #[error("Winners must be named for every pot")]
+    PotWinnersMismatch = 24,
+
+    /// Player already committed a shuffle seed for this hand
+    #[error("Seed already committed")]
+    SeedAlreadyCommitted = 25,
+
+    /// Commitments are no longer accepted for this hand
+    #[error("Seed commitments are closed")]
+    CommitPhaseClosed = 26,
+
+    /// Reveals open once every player has committed or the commit window ends
+    #[error("Seed reveals are not open")]
+    RevealPhaseNotOpen = 27,
+
+    /// Revealed seed does not match the player's commitment
+    #[error("Revealed seed does not match commitment")]
+    InvalidSeedReveal = 28,
+
+    /// Not every player has revealed their seed yet
+    #[error("Deck shuffle is not complete")]
+    ShuffleIncomplete = 29,
+
+    /// Cards for the current street have not been unmasked by every player
+    #[error("Cards have not been dealt yet")]
+    CardsNotDealt = 30,
+
+    /// Encrypted deck or unmasked cards are the wrong size or out of range
+    #[error("Encrypted cards are malformed")]
+    InvalidEncryptedCards = 31,
+
+    /// Nothing is waiting on this player to encrypt or unmask
+    #[error("No cards are waiting on this player")]
+    NoCardsPending = 32,
+
+    /// Showdown cannot be settled until every dealt-in player reveals their key
+    #[error("Waiting on players to reveal their keys")]
+    KeysNotRevealed = 33,
+
+    /// Revealed key pair does not match the player's commitment
+    #[error("Revealed key does not match commitment")]
+    InvalidKeyReveal = 34,
+
+    /// Player was not dealt into the current hand
+    #[error("Player was not dealt into this hand")]
+    NotDealtIn = 35,
+
+    /// Randomness account is missing, malformed or not owned by the table's oracle
+    #[error("Invalid randomness account")]
+    InvalidRandomnessAccount = 36,
+
+    /// Oracle has not published randomness into the account yet
+    #[error("Randomness has not been fulfilled")]
+    RandomnessNotFulfilled = 37,
+
+    /// Randomness is too old or already seeded a hand
+    #[error("Randomness is stale")]
+    StaleRandomness = 38,
+
+    /// Table draws its deck seed from an oracle instead of player seeds
+    #[error("Player seeds are disabled at this table")]
+    PlayerSeedsDisabled = 39,
+
+    /// Nobody is holding up the hand past the action deadline
+    #[error("Action has not timed out")]
+    ActionNotTimedOut = 40,
+
+    /// Game state account is not the PDA for the authority and table id
+    #[error("Invalid game state account")]
+    InvalidGameAccount = 41,
+
+    /// Tables seat between 2 and `MAX_PLAYERS` players
+    #[error("Invalid number of seats")]
+    InvalidMaxPlayers = 42,
+
+    /// Token account is not an SPL token account for the table's mint
+    #[error("Invalid token account")]
+    InvalidTokenAccount = 43,
+
+    /// An account the instruction writes to was passed read-only
+    #[error("Account must be writable")]
+    AccountNotWritable = 44,
+
+    /// Mint account is not an initialized SPL token mint
+    #[error("Invalid mint")]
+    InvalidMint = 45,
+
+    /// Table config has a zero or inverted blind, an ante above the big
+    /// blind, a bad buy-in range or decimals that do not match the mint
+    #[error("Invalid table config")]
+    InvalidTableConfig = 46,
+
+    /// Mint has a Token-2022 extension, such as a transfer fee or frozen
+    /// default accounts, that would stop the vault backing every chip
+    #[error("Unsupported mint")]
+    UnsupportedMint = 47,
+
+    /// `WithdrawRake` found no rake to withdraw
+    #[error("No rake accrued")]
+    NoRakeAccrued = 48,
+
+    /// Seat is past the end of the table, or not the one the player is in
+    #[error("Invalid seat")]
+    InvalidSeat = 49,
+
+    /// Someone is already sitting in the chosen seat
+    #[error("Seat is taken")]
+    SeatTaken = 50,
+
+    /// `SitOut`, `SitIn` or `LeaveAfterHand` does not apply to the seat's
+    /// current status
+    #[error("Invalid seat status")]
+    InvalidSeatStatus = 51,
+
+    /// Player already acted and has only faced a short all-in since, which
+    /// does not let them raise again
+    #[error("Betting has not been reopened")]
+    BettingNotReopened = 52,
+
+    /// Bet or raise is above what the table's betting structure allows
+    #[error("Bet or raise is above the limit")]
+    BetTooLarge = 53,
+
+    /// Fixed limit street already has as many raises as the table allows
+    #[error("Raises are capped for this street")]
+    RaiseCapReached = 54,
+}
+
+impl PokerError {
+    /// Translate a program error from a failed transaction back into a
+    /// `PokerError`, for off-chain tools that want to show players a reason.
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => num_traits::FromPrimitive::from_u32(*code),
+            _ => None,
+        }
+    }
+}
+
+impl From<PokerError> for ProgramError {
+    fn from(e: PokerError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for PokerError {
+    fn type_of() -> &'static str {
+        "PokerError"
+    }
+}
+
+impl PrintProgramError for PokerError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
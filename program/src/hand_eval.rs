@@ -0,0 +1,232 @@
+//! Texas Hold'em hand evaluation.
+//!
+//! Cards are `u8` values in `0..52`: `rank = card % 13` (0 = deuce through
+//! 12 = ace) and `suit = card / 13`, the same order `generateDeck` uses in
+//! the frontend. The evaluator only touches `core` and fixed-size arrays, so
+//! it runs inside the program without heap allocation and in a small, fixed
+//! number of steps regardless of the input.
+
+use core::cmp::Ordering;
+
+pub type Card = u8;
+
+pub const DECK_SIZE: u8 = 52;
+pub const RANKS: u8 = 13;
+pub const SUITS: u8 = 4;
+
+const ACE: u8 = 12;
+
+pub fn rank(card: Card) -> u8 {
+    card % RANKS
+}
+
+pub fn suit(card: Card) -> u8 {
+    card / RANKS
+}
+
+/// Hand categories, weakest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+impl HandCategory {
+    const ALL: [HandCategory; 9] = [
+        HandCategory::HighCard,
+        HandCategory::OnePair,
+        HandCategory::TwoPair,
+        HandCategory::ThreeOfAKind,
+        HandCategory::Straight,
+        HandCategory::Flush,
+        HandCategory::FullHouse,
+        HandCategory::FourOfAKind,
+        HandCategory::StraightFlush,
+    ];
+}
+
+/// Strength of the best five-card hand. Ordering two values compares the
+/// hands with full tie-breaking; equal values are a chop.
+///
+/// Packed as the category in bits 20..24 followed by up to five 4-bit ranks,
+/// most significant first: the ranks that decide ties within the category
+/// (pair rank before kickers, top card of a straight, and so on).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    fn new(category: HandCategory, tiebreak: &[u8]) -> Self {
+        let mut value = (category as u32) << 20;
+        for (position, &r) in tiebreak.iter().take(5).enumerate() {
+            value |= (r as u32) << (16 - 4 * position);
+        }
+        HandRank(value)
+    }
+
+    pub fn category(&self) -> HandCategory {
+        HandCategory::ALL[(self.0 >> 20) as usize]
+    }
+
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+/// Evaluate the best five-card hand from five to seven cards. Returns `None`
+/// for a wrong card count, an out-of-range card or a duplicated card.
+pub fn evaluate(cards: &[Card]) -> Option<HandRank> {
+    if !(5..=7).contains(&cards.len()) {
+        return None;
+    }
+
+    let mut seen: u64 = 0;
+    let mut rank_counts = [0u8; RANKS as usize];
+    let mut suit_masks = [0u16; SUITS as usize];
+
+    for &card in cards {
+        if card >= DECK_SIZE || seen & (1 << card) != 0 {
+            return None;
+        }
+        seen |= 1 << card;
+        rank_counts[rank(card) as usize] += 1;
+        suit_masks[suit(card) as usize] |= 1 << rank(card);
+    }
+
+    let rank_mask = suit_masks.iter().fold(0, |mask, suit_mask| mask | suit_mask);
+
+    // Straight flush and flush: with at most seven cards only one suit can
+    // hold five or more of them
+    if let Some(&flush_mask) = suit_masks.iter().find(|m| m.count_ones() >= 5) {
+        if let Some(high) = straight_high(flush_mask) {
+            return Some(HandRank::new(HandCategory::StraightFlush, &[high]));
+        }
+        let mut ranks = [0u8; 5];
+        top_ranks(flush_mask, &mut ranks);
+        return Some(HandRank::new(HandCategory::Flush, &ranks));
+    }
+
+    // Group ranks by how many copies were dealt, highest rank first
+    let mut quads = None;
+    let mut trips = [0u8; 2];
+    let mut trip_count = 0;
+    let mut pairs = [0u8; 3];
+    let mut pair_count = 0;
+    for r in (0..RANKS).rev() {
+        match rank_counts[r as usize] {
+            4 => quads = quads.or(Some(r)),
+            3 => {
+                trips[trip_count] = r;
+                trip_count += 1;
+            }
+            2 => {
+                pairs[pair_count] = r;
+                pair_count += 1;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(quad) = quads {
+        let kicker = highest_excluding(rank_mask, &[quad]);
+        return Some(HandRank::new(HandCategory::FourOfAKind, &[quad, kicker]));
+    }
+
+    if trip_count >= 1 && (trip_count >= 2 || pair_count >= 1) {
+        // A second set of trips plays as the pair
+        let pair = if trip_count >= 2 {
+            trips[1].max(pairs[0])
+        } else {
+            pairs[0]
+        };
+        return Some(HandRank::new(HandCategory::FullHouse, &[trips[0], pair]));
+    }
+
+    if let Some(high) = straight_high(rank_mask) {
+        return Some(HandRank::new(HandCategory::Straight, &[high]));
+    }
+
+    if trip_count == 1 {
+        let mut kickers = [0u8; 2];
+        top_ranks(rank_mask & !(1 << trips[0]), &mut kickers);
+        return Some(HandRank::new(
+            HandCategory::ThreeOfAKind,
+            &[trips[0], kickers[0], kickers[1]],
+        ));
+    }
+
+    if pair_count >= 2 {
+        // A third pair can still supply the kicker
+        let kicker = highest_excluding(rank_mask, &[pairs[0], pairs[1]]);
+        return Some(HandRank::new(
+            HandCategory::TwoPair,
+            &[pairs[0], pairs[1], kicker],
+        ));
+    }
+
+    if pair_count == 1 {
+        let mut kickers = [0u8; 3];
+        top_ranks(rank_mask & !(1 << pairs[0]), &mut kickers);
+        return Some(HandRank::new(
+            HandCategory::OnePair,
+            &[pairs[0], kickers[0], kickers[1], kickers[2]],
+        ));
+    }
+
+    let mut ranks = [0u8; 5];
+    top_ranks(rank_mask, &mut ranks);
+    Some(HandRank::new(HandCategory::HighCard, &ranks))
+}
+
+/// Compare two players' hole cards on a shared board. `None` if either set
+/// of cards is invalid.
+pub fn compare(hole_a: &[Card; 2], hole_b: &[Card; 2], board: &[Card; 5]) -> Option<Ordering> {
+    let a = evaluate(&seven(hole_a, board))?;
+    let b = evaluate(&seven(hole_b, board))?;
+    Some(a.cmp(&b))
+}
+
+/// Combine two hole cards with a five-card board
+pub fn seven(hole: &[Card; 2], board: &[Card; 5]) -> [Card; 7] {
+    [
+        hole[0], hole[1], board[0], board[1], board[2], board[3], board[4],
+    ]
+}
+
+/// Highest card of the best straight in a rank mask, counting the ace as
+/// low for the wheel (A-2-3-4-5 plays as five-high)
+fn straight_high(rank_mask: u16) -> Option<u8> {
+    // Shift ranks up one bit and copy the ace into bit 0
+    let mask = (rank_mask as u32) << 1 | ((rank_mask as u32) >> ACE & 1);
+    (4..=ACE as u32 + 1)
+        .rev()
+        .find(|&top| mask >> (top - 4) & 0b11111 == 0b11111)
+        .map(|top| (top - 1) as u8)
+}
+
+/// Fill `out` with the highest ranks in `mask`, descending
+fn top_ranks(mask: u16, out: &mut [u8]) {
+    let mut filled = 0;
+    for r in (0..RANKS).rev() {
+        if filled == out.len() {
+            break;
+        }
+        if mask & (1 << r) != 0 {
+            out[filled] = r;
+            filled += 1;
+        }
+    }
+}
+
+fn highest_excluding(mask: u16, exclude: &[u8]) -> u8 {
+    let mask = exclude.iter().fold(mask, |m, &r| m & !(1 << r));
+    let mut top = [0u8; 1];
+    top_ranks(mask, &mut top);
+    top[0]
+}
@@ -3,25 +3,49 @@ use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint,
     entrypoint::ProgramResult,
+    log::sol_log_data,
     msg,
     program::{invoke, invoke_signed},
-    program_error::ProgramError,
-    program_pack::Pack,
+    program_error::{PrintProgramError, ProgramError},
     pubkey::Pubkey,
-    system_instruction,
-    sysvar::{rent::Rent, Sysvar},
+    system_instruction, system_program,
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
-use spl_token::state::Account as TokenAccount;
+
+pub mod accounts;
+pub mod custody;
+pub mod error;
+pub mod hand_eval;
+#[cfg(not(target_os = "solana"))]
+pub mod mock_oracle;
+pub mod randomness;
+pub mod shuffle;
+pub mod sra;
+pub mod token;
+
+pub use error::PokerError;
+
+use accounts::{
+    AuthorityAccounts, BuyInAccounts, CashOutAccounts, EndRoundAccounts, InitializeGameAccounts,
+    InitializeNativeGameAccounts, PlayerAccounts, WithdrawRakeAccounts,
+};
+use custody::{Custody, VaultTransfer};
+use randomness::{OracleRandomness, PlayerSeeds, RandomnessSource};
 
 // Declare and export the program's entrypoint
 entrypoint!(process_instruction);
 
 // Constants
-const USDC_DECIMALS: u8 = 6; // USDC has 6 decimals
-const MAX_BUY_IN: u64 = 5_000_000; // $5 in USDC (5 * 10^6)
-const SMALL_BLIND: u64 = 25_000; // $0.025 USDC
-const BIG_BLIND: u64 = 50_000; // $0.05 USDC
 const POT_SEED: &[u8] = b"poker_pot";
+pub const GAME_SEED: &[u8] = b"poker_game";
+pub const MAX_PLAYERS: u8 = 10;
+pub const MAX_NAME_LEN: usize = 32; // Seat names are generated as `Player_N`
+const SHUFFLE_PHASE_SECS: i64 = 120; // Length of each commit and reveal window
+const BOARD_CARDS: usize = 5;
+pub const ENCRYPT_CHUNK: usize = 26; // Encrypted cards that fit in one transaction
+const HOLE_CARDS_STEP: u8 = 1; // Deal steps: hole cards, then the flop, turn and river
+const ACTION_TIMEOUT_SECS: i64 = 60; // Time each player has to act, deal or show
+pub const MAX_RAKE_BPS: u16 = 1_000; // 10%
 
 // Program entrypoint's implementation
 pub fn process_instruction(
@@ -29,16 +53,21 @@ pub fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let instruction = PokerInstruction::try_from_slice(instruction_data)?;
-
-    match instruction {
-        PokerInstruction::InitializeGame { max_players } => {
+    let instruction = PokerInstruction::try_from_slice(instruction_data)
+        .map_err(|_| PokerError::InvalidInstruction)?;
+
+    let result = match instruction {
+        PokerInstruction::InitializeGame {
+            max_players,
+            table_id,
+            config,
+        } => {
             msg!("Instruction: InitializeGame");
-            initialize_game(program_id, accounts, max_players)
+            initialize_game(program_id, accounts, max_players, table_id, config)
         }
-        PokerInstruction::BuyIn { amount } => {
+        PokerInstruction::BuyIn { amount, seat } => {
             msg!("Instruction: BuyIn");
-            buy_in(program_id, accounts, amount)
+            buy_in(program_id, accounts, amount, seat)
         }
         PokerInstruction::StartRound => {
             msg!("Instruction: StartRound");
@@ -56,48 +85,140 @@ pub fn process_instruction(
             msg!("Instruction: Raise");
             raise(program_id, accounts, amount)
         }
-        PokerInstruction::EndRound { winner_index } => {
+        PokerInstruction::EndRound => {
             msg!("Instruction: EndRound");
-            end_round(program_id, accounts, winner_index)
+            end_round(program_id, accounts)
         }
         PokerInstruction::CashOut => {
             msg!("Instruction: CashOut");
             cash_out(program_id, accounts)
         }
+        PokerInstruction::Check => {
+            msg!("Instruction: Check");
+            check(program_id, accounts)
+        }
+        PokerInstruction::Bet { amount } => {
+            msg!("Instruction: Bet");
+            bet(program_id, accounts, amount)
+        }
+        PokerInstruction::AllIn => {
+            msg!("Instruction: AllIn");
+            all_in(program_id, accounts)
+        }
+        PokerInstruction::CommitSeed { commitment } => {
+            msg!("Instruction: CommitSeed");
+            commit_seed(program_id, accounts, commitment)
+        }
+        PokerInstruction::RevealSeed { seed } => {
+            msg!("Instruction: RevealSeed");
+            reveal_seed(program_id, accounts, seed)
+        }
+        PokerInstruction::EncryptDeck { cards, key_commitment } => {
+            msg!("Instruction: EncryptDeck");
+            encrypt_deck(program_id, accounts, cards, key_commitment)
+        }
+        PokerInstruction::UnmaskCards { cards } => {
+            msg!("Instruction: UnmaskCards");
+            unmask_cards(program_id, accounts, cards)
+        }
+        PokerInstruction::RevealKey {
+            encryption_key,
+            decryption_key,
+        } => {
+            msg!("Instruction: RevealKey");
+            reveal_key(program_id, accounts, encryption_key, decryption_key)
+        }
+        PokerInstruction::SetRandomnessOracle { oracle } => {
+            msg!("Instruction: SetRandomnessOracle");
+            set_randomness_oracle(program_id, accounts, oracle)
+        }
+        PokerInstruction::TimeoutAction { sit_out } => {
+            msg!("Instruction: TimeoutAction");
+            timeout_action(program_id, accounts, sit_out)
+        }
+        PokerInstruction::InitializeNativeGame {
+            max_players,
+            table_id,
+            config,
+        } => {
+            msg!("Instruction: InitializeNativeGame");
+            initialize_native_game(program_id, accounts, max_players, table_id, config)
+        }
+        PokerInstruction::WithdrawRake => {
+            msg!("Instruction: WithdrawRake");
+            withdraw_rake(program_id, accounts)
+        }
+        PokerInstruction::SitOut => {
+            msg!("Instruction: SitOut");
+            sit_out(program_id, accounts)
+        }
+        PokerInstruction::SitIn => {
+            msg!("Instruction: SitIn");
+            sit_in(program_id, accounts)
+        }
+        PokerInstruction::LeaveAfterHand => {
+            msg!("Instruction: LeaveAfterHand");
+            leave_after_hand(program_id, accounts)
+        }
+    };
+
+    if let Err(error) = &result {
+        error.print::<PokerError>();
     }
+
+    result
 }
 
 // Instruction enum
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub enum PokerInstruction {
-    /// Initialize a new poker game
+    /// Initialize a new poker game. The program creates the game state
+    /// account at the PDA of `[GAME_SEED, authority, table_id]`, sized for
+    /// `max_players` seats and funded rent-exempt by the authority. `config`
+    /// sets the stakes; its decimals must match the mint's. The mint can be
+    /// any SPL Token or Token-2022 mint without a pot-breaking extension.
     /// Accounts expected:
     /// 0. `[writable, signer]` Game authority (house)
-    /// 1. `[writable]` Game state account
-    /// 2. `[]` USDC mint
-    /// 3. `[writable]` Pot token account (PDA)
-    /// 4. `[]` Token program
-    /// 5. `[]` System program
-    /// 6. `[]` Rent sysvar
-    InitializeGame { max_players: u8 },
-
-    /// Buy into the game with USDC (max $5)
+    /// 1. `[writable]` Game state account (PDA)
+    /// 2. `[]` Mint the table plays in
+    /// 3. `[writable]` Pot token account (pot authority's associated token
+    ///    account, created here)
+    /// 4. `[]` Pot authority (PDA of `[POT_SEED, game state]`)
+    /// 5. `[]` Token program that owns the mint
+    /// 6. `[]` Associated token account program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    InitializeGame {
+        max_players: u8,
+        table_id: u64,
+        config: TableConfig,
+    },
+
+    /// Buy into the game with the table's tokens, within its buy-in range.
+    /// New players sit in `seat`, or the first empty seat with `None`, and
+    /// keep it until they cash out, and are dealt in from the next hand.
+    /// Players already seated top up in place between hands.
     /// Accounts expected:
     /// 0. `[writable, signer]` Player account
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Player's USDC token account
-    /// 3. `[writable]` Pot USDC token account (PDA)
-    /// 4. `[]` Token program
-    BuyIn { amount: u64 },
-
-    /// Start a new round (posts blinds automatically)
+    /// 2. `[writable]` Player's token account
+    /// 3. `[writable]` Pot token account
+    /// 4. `[]` Mint
+    /// 5. `[]` Token program
+    BuyIn { amount: u64, seat: Option<u8> },
+
+    /// Start a new round (posts blinds automatically from chip stacks).
+    /// Every player with chips must have revealed a shuffle seed; after the
+    /// reveal deadline, players who did not are sat out until they commit a
+    /// seed again, and anyone who committed without revealing forfeits a big
+    /// blind: as dead money in the hand, or to the players who did reveal
+    /// when too few did to deal and the shuffle restarts.
+    /// Tables with a randomness oracle skip the seeds and read the deck seed
+    /// from a fresh, fulfilled oracle account instead.
     /// Accounts expected:
     /// 0. `[writable, signer]` Dealer/authority
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Small blind player USDC account
-    /// 3. `[writable]` Big blind player USDC account
-    /// 4. `[writable]` Pot USDC token account
-    /// 5. `[]` Token program
+    /// 2. `[]` Oracle randomness account (only for tables with an oracle)
     StartRound,
 
     /// Fold current hand
@@ -106,302 +227,2406 @@ pub enum PokerInstruction {
     /// 1. `[writable]` Game state account
     Fold,
 
-    /// Call current bet
+    /// Call current bet (use `Check` when there is nothing to call)
     /// Accounts expected:
     /// 0. `[writable, signer]` Player account
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Player's USDC token account
-    /// 3. `[writable]` Pot USDC token account
-    /// 4. `[]` Token program
     Call,
 
-    /// Raise bet
+    /// Raise an existing bet (use `Bet` to open a street)
     /// Accounts expected:
     /// 0. `[writable, signer]` Player account
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Player's USDC token account
-    /// 3. `[writable]` Pot USDC token account
-    /// 4. `[]` Token program
     Raise { amount: u64 },
 
-    /// End round and distribute pot to winner
+    /// Settle the hand once it reaches showdown. If more than one player is
+    /// left, every dealt-in player must have revealed their key so the
+    /// program can decrypt the hands and award each pot to its best hand;
+    /// tied hands chop. Cards that fail to decrypt are replayed through
+    /// every player's unmasks with their key: a player whose unmasks do not
+    /// match forfeits their chips in the pot to the rest, and otherwise the
+    /// hand is a misdeal.
     /// Accounts expected:
     /// 0. `[signer]` Game authority
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Pot USDC token account (PDA)
-    /// 3. `[writable]` Winner's USDC token account
-    /// 4. `[]` Pot PDA authority
-    /// 5. `[]` Token program
-    EndRound { winner_index: u8 },
+    /// 2. `[]` Pot token account
+    EndRound,
 
-    /// Cash out chips to USDC
+    /// Cash out chips to tokens
     /// Accounts expected:
     /// 0. `[writable, signer]` Player account
     /// 1. `[writable]` Game state account
-    /// 2. `[writable]` Pot USDC token account (PDA)
-    /// 3. `[writable]` Player's USDC token account
-    /// 4. `[]` Pot PDA authority
-    /// 5. `[]` Token program
+    /// 2. `[writable]` Pot token account
+    /// 3. `[writable]` Player's token account
+    /// 4. `[]` Pot authority (PDA)
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
     CashOut,
+
+    /// Check (pass the action without betting)
+    /// Accounts expected:
+    /// 0. `[writable, signer]` Player account
+    /// 1. `[writable]` Game state account
+    Check,
+
+    /// Open the betting on a street
+    /// Accounts expected:
+    /// 0. `[writable, signer]` Player account
+    /// 1. `[writable]` Game state account
+    Bet { amount: u64 },
+
+    /// Put every remaining chip into the pot, as a bet, raise or short call
+    /// Accounts expected:
+    /// 0. `[writable, signer]` Player account
+    /// 1. `[writable]` Game state account
+    AllIn,
+
+    /// Commit to a secret seed for the next hand's shuffle, as
+    /// `shuffle::commitment(player, seed)`
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    CommitSeed { commitment: [u8; 32] },
+
+    /// Reveal the seed behind this hand's commitment
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    RevealSeed { seed: [u8; 32] },
+
+    /// Encrypt every card in `GameState.deck` with the player's key and
+    /// shuffle it, taking turns in dealing order. The deck does not fit in one
+    /// transaction, so the pass is posted in order over several calls of at
+    /// most `ENCRYPT_CHUNK` cards, each with the same `key_commitment`:
+    /// `sra::key_commitment(player, encryption_key, decryption_key)`.
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    EncryptDeck {
+        cards: Vec<sra::Point>,
+        key_commitment: [u8; 32],
+    },
+
+    /// Strip the player's layer from the cards of the next deal step, in
+    /// dealing order: first everyone else's hole cards, then the flop, turn
+    /// and river once betting reaches them. A board card that does not
+    /// decode sends the hand to showdown, where every dealt-in player must
+    /// reveal their key for `EndRound` to find who broke it.
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    UnmaskCards { cards: Vec<sra::Point> },
+
+    /// Reveal the key pair committed to in `EncryptDeck` for showdown
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    RevealKey {
+        encryption_key: sra::Key,
+        decryption_key: sra::Key,
+    },
+
+    /// Seed decks from a VRF oracle program's randomness accounts, or go
+    /// back to player commit-reveal seeds with `None`
+    /// Accounts expected:
+    /// 0. `[signer]` Game authority
+    /// 1. `[writable]` Game state account
+    SetRandomnessOracle { oracle: Option<Pubkey> },
+
+    /// Move the hand on once the player it is waiting for has let the action
+    /// deadline pass. A stalled bettor checks if they can and folds if not.
+    /// Cards and keys cannot be supplied for someone else, so a stalled
+    /// encryption pass, unmask or showdown key calls the hand off: the
+    /// stalled players' chips in the pot are split between everyone else
+    /// dealt in, who get their own chips back. With `sit_out` the stalled
+    /// players are not dealt into later hands until they commit a seed or
+    /// buy in again.
+    /// Accounts expected:
+    /// 0. `[signer]` Anyone
+    /// 1. `[writable]` Game state account
+    TimeoutAction { sit_out: bool },
+
+    /// Initialize a table that plays for native SOL. Lamports are held in
+    /// the pot authority PDA itself, created here as a program-owned escrow,
+    /// and `config` is in lamports with 9 decimals. `BuyIn`, `CashOut` and
+    /// `EndRound` take the escrow as both pot account and pot authority, the
+    /// player's wallet as their token account, the native mint as the mint
+    /// and the system program as the token program.
+    /// Accounts expected:
+    /// 0. `[writable, signer]` Game authority (house)
+    /// 1. `[writable]` Game state account (PDA)
+    /// 2. `[writable]` Escrow (PDA of `[POT_SEED, game state]`, created here)
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    InitializeNativeGame {
+        max_players: u8,
+        table_id: u64,
+        config: TableConfig,
+    },
+
+    /// Move every chip of rake accrued at settlement out of the vault to the
+    /// house's treasury. On native tables the treasury is a wallet.
+    /// Accounts expected:
+    /// 0. `[signer]` Game authority
+    /// 1. `[writable]` Game state account
+    /// 2. `[writable]` Pot token account
+    /// 3. `[writable]` Treasury token account
+    /// 4. `[]` Pot authority (PDA)
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    WithdrawRake,
+
+    /// Stop being dealt in from the next hand while keeping the seat and
+    /// chips. A hand the player is already in plays out as normal.
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    SitOut,
+
+    /// Be dealt in again from the next hand, after sitting out or instead of
+    /// leaving
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    SitIn,
+
+    /// Leave the table once the current hand is over. The player is not dealt
+    /// into later hands, and their seat is freed when they cash out, or when
+    /// the hand settles if they have no chips left. Between hands a player
+    /// without chips leaves straight away.
+    /// Accounts expected:
+    /// 0. `[signer]` Player account
+    /// 1. `[writable]` Game state account
+    LeaveAfterHand,
 }
 
 // Game state structure
+//
+// Custody model: funds only move on `BuyIn` and `CashOut`. Once bought in,
+// chips are an internal ledger backed by the vault (see `custody`), and every
+// instruction that touches the vault checks that
+// `sum(player.chips) + pot_total + rake_accrued <= vault balance`. Anyone can
+// send funds to the vault, so a surplus is tolerated and backs no chips.
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
 pub struct GameState {
     pub authority: Pubkey,
-    pub usdc_mint: Pubkey,
-    pub pot_token_account: Pubkey,
-    pub pot_bump: u8,
+    pub mint: Pubkey,
+    pub pot_token_account: Pubkey, // Vault holding the tokens behind every chip
+    pub pot_bump: u8, // Bump of `pot_authority`
     pub max_players: u8,
-    pub current_players: u8,
-    pub players: Vec<Player>,
+    pub current_players: u8, // Seats taken
+    pub players: Vec<Option<Player>>, // One entry per seat, `None` while empty
     pub current_bet: u64,
     pub pot_total: u64,
     pub game_stage: GameStage,
     pub current_player_turn: u8,
     pub dealer_index: u8,
-    pub small_blind: u64,
-    pub big_blind: u64,
+    pub config: TableConfig, // Blinds, ante and buy-in range
+    pub pots: Vec<SidePot>, // Filled in at showdown
+    pub commit_deadline: i64, // Unix time; 0 while no shuffle is under way
+    pub reveal_deadline: i64,
+    pub deck: Vec<sra::Point>, // Deck as encrypted and unmasked so far, see `sra`
+    pub encrypted_deck: Vec<sra::Point>, // Dealt cards under every player's key
+    pub encryption_passes: u8,
+    pub cards_revealed: u8, // Deal steps every dealt-in player has unmasked
+    pub board: Vec<u8>,
+    pub randomness_oracle: Option<Pubkey>, // `None` shuffles from player seeds
+    pub last_randomness_slot: u64, // Oracle results must be newer than this
+    pub action_deadline: i64, // Unix time; 0 while no hand is under way
+    pub table_id: u64,
+    pub game_bump: u8,
+    pub pot_authority: Pubkey, // PDA that owns the vault and signs payouts
+    pub token_program: Pubkey, // SPL Token or Token-2022, whichever owns the mint
+    pub custody: Custody, // Whether the vault holds tokens or lamports
+    pub rake_accrued: u64, // Rake held in the vault until `WithdrawRake`
+    pub saw_flop: bool, // Whether this hand got past preflop
+    pub small_blind_seat: Option<u8>, // Blinds of the last settled hand, dead or
+    pub big_blind_seat: Option<u8>, // not; `None` before the first
+    pub last_raise_size: u64, // Largest full bet or raise on this street
+    pub raises_this_street: u8, // Full raises after the opening bet or blinds
+    pub board_disputed: bool, // A board card did not decode; keys settle who is to blame
+    pub encrypted_cards: u8, // Cards of the current encryption pass posted so far
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
-pub struct Player {
-    pub pubkey: Pubkey,
-    pub name: String,
-    pub chips: u64, // In USDC micro-units (6 decimals)
-    pub current_bet: u64,
-    pub is_active: bool,
-    pub has_folded: bool,
-}
+impl GameState {
+    /// Account size that holds a table of `max_players` seats at its
+    /// largest: every seat taken, every field set and a pot per seat
+    pub fn space(max_players: u8) -> usize {
+        let seats = max_players as usize;
+        let dealt_cards = 2 * seats + BOARD_CARDS;
+
+        32 + 32 + 32 // authority, mint, pot_token_account
+            + 1 + 1 + 1 // pot_bump, max_players, current_players
+            + 4 + seats * (1 + Player::SPACE) // players
+            + 8 + 8 // current_bet, pot_total
+            + 1 + 1 + 1 // game_stage, current_player_turn, dealer_index
+            + TableConfig::SPACE // config
+            + 4 + seats * (8 + 4 + seats) // pots
+            + 8 + 8 // commit_deadline, reveal_deadline
+            + 4 + hand_eval::DECK_SIZE as usize * 32 // deck
+            + 4 + dealt_cards * 32 // encrypted_deck
+            + 1 + 1 // encryption_passes, cards_revealed
+            + 4 + BOARD_CARDS // board
+            + 1 + 32 // randomness_oracle
+            + 8 + 8 // last_randomness_slot, action_deadline
+            + 8 + 1 // table_id, game_bump
+            + 32 + 32 // pot_authority, token_program
+            + 1 + 8 + 1 // custody, rake_accrued, saw_flop
+            + 2 + 2 // small_blind_seat, big_blind_seat
+            + 8 + 1 // last_raise_size, raises_this_street
+            + 1 + 1 // board_disputed, encrypted_cards
+    }
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
-pub enum GameStage {
-    Waiting,
-    PreFlop,
-    Flop,
-    Turn,
-    River,
-    Showdown,
+    /// An empty table waiting for players. The vault fields are left unset
+    /// for the initializing instruction to fill in.
+    fn new(authority: Pubkey, table_id: u64, game_bump: u8, max_players: u8, config: TableConfig) -> Self {
+        Self {
+            authority,
+            mint: Pubkey::default(),
+            pot_token_account: Pubkey::default(),
+            pot_bump: 0,
+            max_players,
+            current_players: 0,
+            players: vec![None; max_players as usize],
+            current_bet: 0,
+            pot_total: 0,
+            game_stage: GameStage::Waiting,
+            current_player_turn: 0,
+            dealer_index: 0,
+            config,
+            pots: Vec::new(),
+            commit_deadline: 0,
+            reveal_deadline: 0,
+            deck: Vec::new(),
+            encrypted_deck: Vec::new(),
+            encryption_passes: 0,
+            cards_revealed: 0,
+            board: Vec::new(),
+            randomness_oracle: None,
+            last_randomness_slot: 0,
+            action_deadline: 0,
+            table_id,
+            game_bump,
+            pot_authority: Pubkey::default(),
+            token_program: Pubkey::default(),
+            custody: Custody::Token,
+            rake_accrued: 0,
+            saw_flop: false,
+            small_blind_seat: None,
+            big_blind_seat: None,
+            last_raise_size: 0,
+            raises_this_street: 0,
+            board_disputed: false,
+            encrypted_cards: 0,
+        }
+    }
+
+    /// `amount` of the table's mint in whole tokens, for logs
+    pub fn ui_amount(&self, amount: u64) -> String {
+        token::ui_amount(amount, self.config.decimals)
+    }
+
+    /// Seated players in seat order, skipping empty seats
+    pub fn seated(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter().flatten()
+    }
+
+    fn seated_mut(&mut self) -> impl Iterator<Item = &mut Player> {
+        self.players.iter_mut().flatten()
+    }
+
+    /// Player sitting in `seat`, if anyone is
+    pub fn seat(&self, seat: usize) -> Option<&Player> {
+        self.players.get(seat).and_then(Option::as_ref)
+    }
+
+    /// Seat of the player with `pubkey`
+    pub fn seat_of(&self, pubkey: &Pubkey) -> Result<usize, ProgramError> {
+        self.seated()
+            .find(|p| p.pubkey == *pubkey)
+            .map(|p| p.seat_index as usize)
+            .ok_or_else(|| PokerError::PlayerNotFound.into())
+    }
+
+    /// Player in a seat known to be taken: one from `seat_of`, a pot or
+    /// another seated player's lookup
+    fn player(&self, seat: usize) -> &Player {
+        self.players[seat].as_ref().expect("seat is taken")
+    }
+
+    fn player_mut(&mut self, seat: usize) -> &mut Player {
+        self.players[seat].as_mut().expect("seat is taken")
+    }
+
+    /// Total chips the vault must back: every stack, the current pot and
+    /// the rake not yet withdrawn
+    pub fn chips_in_play(&self) -> Result<u64, ProgramError> {
+        self.seated()
+            .try_fold(self.pot_total, |total, p| total.checked_add(p.chips))
+            .and_then(|total| total.checked_add(self.rake_accrued))
+            .ok_or_else(|| PokerError::Overflow.into())
+    }
+
+    /// Whether a betting street is in progress
+    pub fn is_betting_round(&self) -> bool {
+        matches!(
+            self.game_stage,
+            GameStage::PreFlop | GameStage::Flop | GameStage::Turn | GameStage::River
+        )
+    }
+
+    /// Index of the signing player, provided they hold the action
+    fn acting_player_index(&self, player: &Pubkey) -> Result<usize, ProgramError> {
+        if !self.is_betting_round() {
+            msg!("No betting round in progress");
+            return Err(PokerError::NoActiveBettingRound.into());
+        }
+
+        if self.cards_revealed < self.street_step() {
+            msg!("Waiting on every player to unmask this street's cards");
+            return Err(PokerError::CardsNotDealt.into());
+        }
+
+        let player_index = self.seat_of(player)?;
+
+        let acting = self.player(player_index);
+        if acting.hole_index.is_none() {
+            msg!("Player {} was not dealt into this hand", acting.name);
+            return Err(PokerError::NotDealtIn.into());
+        }
+
+        if acting.has_folded {
+            msg!("Player {} has already folded", acting.name);
+            return Err(PokerError::PlayerFolded.into());
+        }
+
+        if acting.is_all_in {
+            msg!("Player {} is already all-in", acting.name);
+            return Err(PokerError::PlayerAllIn.into());
+        }
+
+        if player_index != self.current_player_turn as usize {
+            msg!(
+                "Waiting on seat {}, not seat {}",
+                self.current_player_turn,
+                player_index
+            );
+            return Err(PokerError::NotYourTurn.into());
+        }
+
+        Ok(player_index)
+    }
+
+    /// Next taken seat going round from `from` whose player matches
+    /// `wanted`, or `from` if there is none
+    fn next_seat(&self, from: u8, wanted: impl Fn(&Player) -> bool) -> u8 {
+        let seats = self.max_players;
+        (1..=seats)
+            .map(|offset| (from + offset) % seats)
+            .find(|&seat| self.seat(seat as usize).is_some_and(&wanted))
+            .unwrap_or(from)
+    }
+
+    /// Next seat after `from` that can still act: dealt in, not folded and
+    /// not all-in
+    fn next_actor(&self, from: u8) -> u8 {
+        self.next_seat(from, Player::can_act)
+    }
+
+    /// Where the button and blinds fall for the players dealt into this
+    /// hand. Heads-up the button posts the small blind. Otherwise a moving
+    /// button posts nothing and the blinds are the next two seats dealt in;
+    /// a dead button makes the big blind move on exactly one seat dealt in
+    /// a hand, so nobody dodges it when players come and go, at the cost of
+    /// a dead small blind or button now and then.
+    fn blind_positions(&self) -> BlindPositions {
+        let dealt_in = |p: &Player| p.hole_index.is_some();
+        let heads_up = self.dealt_in() == 2;
+
+        match self.big_blind_seat {
+            Some(last_big_blind) if self.config.dead_button => {
+                let big_blind = self.next_seat(last_big_blind, dealt_in);
+                if heads_up {
+                    let button = self.next_seat(big_blind, dealt_in);
+                    return BlindPositions {
+                        button,
+                        small_blind: button,
+                        big_blind,
+                    };
+                }
+
+                // Last hand's big blind seat has the small blind, which is
+                // dead if nobody there was dealt in. The button follows last
+                // hand's small blind, even onto an empty seat, but never
+                // onto a blind.
+                let small_blind = last_big_blind;
+                let mut button = self.small_blind_seat.unwrap_or(self.dealer_index);
+                if button == small_blind || button == big_blind {
+                    button = (small_blind + self.max_players - 1) % self.max_players;
+                }
+
+                BlindPositions {
+                    button,
+                    small_blind,
+                    big_blind,
+                }
+            }
+            _ => {
+                let button = if self.seat(self.dealer_index as usize).is_some_and(dealt_in) {
+                    self.dealer_index
+                } else {
+                    self.next_seat(self.dealer_index, dealt_in)
+                };
+                let small_blind = if heads_up {
+                    button
+                } else {
+                    self.next_seat(button, dealt_in)
+                };
+                BlindPositions {
+                    button,
+                    small_blind,
+                    big_blind: self.next_seat(small_blind, dealt_in),
+                }
+            }
+        }
+    }
+
+    /// Remember a settled hand's blinds for the dead button, and move a
+    /// moving button on to the next taken seat
+    fn move_button(&mut self) {
+        let positions = self.blind_positions();
+        self.small_blind_seat = Some(positions.small_blind);
+        self.big_blind_seat = Some(positions.big_blind);
+        if !self.config.dead_button {
+            self.dealer_index = self.next_seat(positions.button, |_| true);
+        }
+    }
+
+    /// Whether seeds can be revealed: every player with chips has committed,
+    /// or the commit window has closed
+    pub fn reveals_open(&self, now: i64) -> bool {
+        let all_committed = self
+            .seated()
+            .filter(|p| p.wants_cards())
+            .all(|p| p.seed_commitment.is_some());
+
+        self.commit_deadline != 0 && (all_committed || now > self.commit_deadline)
+    }
+
+    /// Forget every commitment, seed, key and the deck so the next hand
+    /// starts a fresh shuffle. The board and shown hands stay visible until
+    /// the next deal.
+    fn reset_shuffle(&mut self) {
+        for player in self.seated_mut() {
+            player.seed_commitment = None;
+            player.revealed_seed = None;
+            player.hole_index = None;
+            player.key_commitment = None;
+            player.unmask_step = 0;
+            player.decryption_key = None;
+            player.board_unmasks.clear();
+        }
+        self.commit_deadline = 0;
+        self.reveal_deadline = 0;
+        self.deck.clear();
+        self.encrypted_deck.clear();
+        self.encryption_passes = 0;
+        self.encrypted_cards = 0;
+        self.cards_revealed = 0;
+    }
+
+    /// Number of players dealt into the current hand
+    pub fn dealt_in(&self) -> u8 {
+        self.seated().filter(|p| p.hole_index.is_some()).count() as u8
+    }
+
+    /// Whether every dealt-in player has encrypted the deck
+    pub fn deck_encrypted(&self) -> bool {
+        self.encryption_passes > 0 && self.encryption_passes == self.dealt_in()
+    }
+
+    /// Deal step whose cards betting on the current street needs
+    pub fn street_step(&self) -> u8 {
+        match self.game_stage {
+            GameStage::PreFlop => HOLE_CARDS_STEP,
+            GameStage::Flop => HOLE_CARDS_STEP + 1,
+            GameStage::Turn => HOLE_CARDS_STEP + 2,
+            GameStage::River => HOLE_CARDS_STEP + 3,
+            _ => 0,
+        }
+    }
+
+    /// Deck positions a player unmasks at `step`: every other player's hole
+    /// cards, then three, one and one board cards
+    pub fn unmask_positions(&self, step: u8, hole_index: u8) -> Vec<usize> {
+        let board_start = 2 * self.dealt_in() as usize;
+        let own = 2 * hole_index as usize;
+        match step {
+            HOLE_CARDS_STEP => (0..board_start)
+                .filter(|&position| position != own && position != own + 1)
+                .collect(),
+            2 => (board_start..board_start + 3).collect(),
+            3 => vec![board_start + 3],
+            4 => vec![board_start + 4],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Seat of the player at `hole_index` in dealing order
+    fn dealt_seat(&self, hole_index: u8) -> Option<usize> {
+        self.seated()
+            .find(|p| p.hole_index == Some(hole_index))
+            .map(|p| p.seat_index as usize)
+    }
+
+    /// Fail until every dealt-in player, folded or not, has revealed their
+    /// key
+    fn check_keys_revealed(&self) -> ProgramResult {
+        let missing_keys = self
+            .seated()
+            .filter(|p| p.hole_index.is_some() && p.decryption_key.is_none())
+            .count();
+        if missing_keys > 0 {
+            msg!("Waiting on {} players to reveal their keys", missing_keys);
+            return Err(PokerError::KeysNotRevealed.into());
+        }
+
+        Ok(())
+    }
+
+    /// Decrypt the dealt cards with every revealed key and pick each pot's
+    /// winners. `None` if the cards do not decrypt, for
+    /// `call_off_undecodable` to settle.
+    fn showdown_winners(&mut self) -> Result<Option<Vec<Vec<u8>>>, ProgramError> {
+        if !self.deck_encrypted() {
+            msg!("Waiting on players to encrypt the deck");
+            return Err(PokerError::CardsNotDealt.into());
+        }
+
+        self.check_keys_revealed()?;
+
+        let key = sra::combine_keys(self.seated().filter_map(|p| p.decryption_key));
+        let reveal = |value: sra::Point| sra::decode(sra::decrypt(value, key));
+
+        let board_start = 2 * self.dealt_in() as usize;
+        let mut board = [0u8; BOARD_CARDS];
+        for (index, card) in board.iter_mut().enumerate() {
+            match reveal(self.encrypted_deck[board_start + index]) {
+                Some(revealed) => *card = revealed,
+                None => {
+                    msg!("Board card {} does not decrypt", index);
+                    return Ok(None);
+                }
+            }
+        }
+
+        // A board card shown during the hand that the keys do not bear out
+        // was swapped by whoever broke the unmasking chain, so their hand is
+        // mucked
+        if self.board.iter().zip(&board).any(|(shown, dealt)| shown != dealt) {
+            let Some(seat) = self.board_cheat() else {
+                return Ok(None);
+            };
+            msg!(
+                "Player {} unmasked a board card their key does not match; hand mucked",
+                self.player(seat).name
+            );
+            self.player_mut(seat).has_folded = true;
+            self.build_pots();
+        }
+        self.board = board.to_vec();
+
+        let mut ranks: Vec<Option<hand_eval::HandRank>> = vec![None; self.players.len()];
+        for player in self.players.iter_mut().flatten() {
+            let seat = player.seat_index as usize;
+            let hole_index = match player.hole_index {
+                Some(hole_index) if !player.has_folded => hole_index as usize,
+                _ => continue,
+            };
+
+            let hole = match (
+                reveal(self.encrypted_deck[2 * hole_index]),
+                reveal(self.encrypted_deck[2 * hole_index + 1]),
+            ) {
+                (Some(first), Some(second)) => [first, second],
+                _ => {
+                    msg!("Hole cards for seat {} do not decrypt", seat);
+                    return Ok(None);
+                }
+            };
+
+            // Duplicate cards mean a player encrypted a doctored deck
+            let Some(rank) = hand_eval::evaluate(&hand_eval::seven(&hole, &board)) else {
+                msg!("Seat {} was dealt a duplicate card", seat);
+                return Ok(None);
+            };
+
+            msg!("Seat {} shows {:?}: {:?}", seat, hole, rank.category());
+            player.hole_cards = Some(hole);
+            ranks[seat] = Some(rank);
+        }
+
+        if self.pots.is_empty() {
+            return Ok(None);
+        }
+
+        let pot_winners = self
+            .pots
+            .iter()
+            .map(|pot| {
+                let best = pot.eligible_seats.iter().map(|&seat| ranks[seat as usize]).max();
+                pot.eligible_seats
+                    .iter()
+                    .copied()
+                    .filter(|&seat| ranks[seat as usize] == best.flatten())
+                    .collect()
+            })
+            .collect();
+
+        Ok(Some(pot_winners))
+    }
+
+    /// Hand every chip committed this hand back to the players dealt in who
+    /// put it in. Dead money from players who were not, like a withheld
+    /// seed's big blind, is shared out between them instead.
+    fn misdeal(&mut self) -> ProgramResult {
+        self.forfeit_hand(&[])
+    }
+
+    /// Hand every chip still counted as committed back to its player
+    fn refund_contributions(&mut self) -> ProgramResult {
+        msg!("Misdeal: returning every player's chips");
+        for player in self.seated_mut() {
+            player.chips = player
+                .chips
+                .checked_add(player.total_contribution)
+                .ok_or(PokerError::Overflow)?;
+        }
+        self.pot_total = 0;
+        Ok(())
+    }
+
+    /// Call off a hand that cannot go on without the `stalled` seats. Everyone
+    /// else dealt in gets their chips back and splits what the stalled
+    /// players committed, along with any dead money from players not dealt
+    /// in.
+    fn forfeit_hand(&mut self, stalled: &[usize]) -> ProgramResult {
+        let recipients: Vec<u8> = self
+            .seated()
+            .filter(|p| p.hole_index.is_some() && !stalled.contains(&(p.seat_index as usize)))
+            .map(|p| p.seat_index)
+            .collect();
+        if recipients.is_empty() {
+            return self.refund_contributions();
+        }
+
+        let dead_money = self
+            .seated()
+            .filter(|p| p.hole_index.is_none() && p.total_contribution > 0)
+            .map(|p| p.seat_index as usize);
+        let forfeiting: Vec<usize> = stalled.iter().copied().chain(dead_money).collect();
+
+        let mut forfeited: u64 = 0;
+        for seat in forfeiting {
+            let player = self.player_mut(seat);
+            forfeited = forfeited
+                .checked_add(player.total_contribution)
+                .ok_or(PokerError::Overflow)?;
+            player.total_contribution = 0;
+            msg!("Player {} forfeits their chips in the pot", player.name);
+        }
+        self.refund_contributions()?;
+
+        self.share_out(recipients, forfeited)
+    }
+
+    /// Split `amount` between `seats`, odd chips going to the seats closest
+    /// to the left of the button
+    fn share_out(&mut self, mut seats: Vec<u8>, amount: u64) -> ProgramResult {
+        seats.sort_unstable_by_key(|&seat| self.seats_after_button(seat));
+        let share = amount / seats.len() as u64;
+        let odd_chips = amount % seats.len() as u64;
+        for (position, &seat) in seats.iter().enumerate() {
+            let player = self.player_mut(seat as usize);
+            player.chips = player
+                .chips
+                .checked_add(share + u64::from((position as u64) < odd_chips))
+                .ok_or(PokerError::Overflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Seats that committed a shuffle seed but have not revealed it
+    fn withheld_seeds(&self) -> Vec<usize> {
+        self.seated()
+            .filter(|p| p.seed_commitment.is_some() && p.revealed_seed.is_none() && p.chips > 0)
+            .map(|p| p.seat_index as usize)
+            .collect()
+    }
+
+    /// Sit out every player who wanted cards but missed the seed reveal, so
+    /// later hands don't wait on them
+    fn sit_out_unrevealed(&mut self) {
+        for player in self.seated_mut() {
+            if player.wants_cards() && player.revealed_seed.is_none() {
+                player.status = SeatStatus::SittingOut;
+                msg!("Player {} missed the seed reveal and is sat out", player.name);
+            }
+        }
+    }
+
+    /// Hold the hand at showdown until every dealt-in player has revealed
+    /// the key that shows who broke a board card
+    fn dispute_board(&mut self) {
+        msg!("Board cards do not decrypt; every player dealt in must reveal their key");
+        self.game_stage = GameStage::Showdown;
+        self.board_disputed = true;
+    }
+
+    /// Seat of the first player whose board unmasks their revealed key does
+    /// not reproduce, replaying each board card from the encrypted deck in
+    /// dealing order
+    fn board_cheat(&self) -> Option<usize> {
+        let mut unmaskers: Vec<&Player> = self.seated().filter(|p| p.hole_index.is_some()).collect();
+        unmaskers.sort_unstable_by_key(|p| p.hole_index);
+        let board_start = 2 * unmaskers.len();
+
+        for index in 0..BOARD_CARDS {
+            let mut card = self.encrypted_deck[board_start + index];
+            for player in &unmaskers {
+                let (Some(&unmasked), Some(key)) =
+                    (player.board_unmasks.get(index), player.decryption_key)
+                else {
+                    break;
+                };
+                if unmasked != sra::decrypt(card, key) {
+                    return Some(player.seat_index as usize);
+                }
+                card = unmasked;
+            }
+        }
+
+        None
+    }
+
+    /// Call off a hand whose cards do not decode. A player whose board
+    /// unmasks do not match their key forfeits as if they had stalled.
+    /// Otherwise a layer was broken during encryption, which no revealed key
+    /// can pin on anyone, and it is a plain misdeal.
+    fn call_off_undecodable(&mut self) -> ProgramResult {
+        match self.board_cheat() {
+            Some(seat) => {
+                msg!(
+                    "Player {} unmasked a board card their key does not match",
+                    self.player(seat).name
+                );
+                self.forfeit_hand(&[seat])
+            }
+            None => self.misdeal(),
+        }
+    }
+
+    /// Seats still to come before `seat` going round from the left of the
+    /// button
+    fn seats_after_button(&self, seat: u8) -> u8 {
+        let seats = self.max_players;
+        (seat + seats - 1 - self.dealer_index % seats) % seats
+    }
+
+    /// Mark every seat sitting out between the button and `big_blind_seat`
+    /// as owing the big blind it was passed over for
+    fn track_missed_blinds(&mut self, big_blind_seat: u8) {
+        let big_blind_reach = self.seats_after_button(big_blind_seat);
+        let missed: Vec<usize> = self
+            .seated()
+            .filter(|p| p.status == SeatStatus::SittingOut && p.chips > 0 && !p.missed_big_blind)
+            .filter(|p| self.seats_after_button(p.seat_index) <= big_blind_reach)
+            .map(|p| p.seat_index as usize)
+            .collect();
+        for seat in missed {
+            let player = self.player_mut(seat);
+            player.missed_big_blind = true;
+            msg!("Player {} missed the big blind", player.name);
+        }
+    }
+
+    /// Seats the hand is waiting on: the next player to encrypt or unmask,
+    /// the player to act, or everyone yet to reveal their key at showdown
+    pub fn stalled_seats(&self) -> Vec<usize> {
+        if self.game_stage == GameStage::Waiting {
+            return Vec::new();
+        }
+
+        if !self.deck_encrypted() {
+            return self.dealt_seat(self.encryption_passes).into_iter().collect();
+        }
+
+        if self.is_betting_round() {
+            if self.cards_revealed < self.street_step() {
+                let step = self.cards_revealed + 1;
+                return self
+                    .seated()
+                    .filter(|p| p.hole_index.is_some() && p.unmask_step < step)
+                    .min_by_key(|p| p.hole_index)
+                    .map(|p| p.seat_index as usize)
+                    .into_iter()
+                    .collect();
+            }
+            return vec![self.current_player_turn as usize];
+        }
+
+        // A lone player left needs nobody's key
+        if self.seated().filter(|p| !p.has_folded).count() < 2 {
+            return Vec::new();
+        }
+        self.seated()
+            .filter(|p| p.hole_index.is_some() && p.decryption_key.is_none())
+            .map(|p| p.seat_index as usize)
+            .collect()
+    }
+
+    /// Give whoever the hand now waits on the full timeout to act
+    fn restart_action_clock(&mut self) -> ProgramResult {
+        self.action_deadline = Clock::get()?.unix_timestamp + ACTION_TIMEOUT_SECS;
+        Ok(())
+    }
+
+    /// Clear per-hand state once a hand is settled or abandoned. Players
+    /// left without chips are busted, or give up their seat if they were
+    /// leaving.
+    fn reset_hand(&mut self) {
+        for seat in self.players.iter_mut() {
+            let Some(player) = seat else { continue };
+            player.current_bet = 0;
+            player.has_folded = false;
+            player.total_contribution = 0;
+            player.is_all_in = false;
+
+            if player.chips == 0 {
+                if player.status == SeatStatus::PendingLeave {
+                    msg!("Player {} left seat {}", player.name, player.seat_index);
+                    *seat = None;
+                    self.current_players -= 1;
+                } else {
+                    player.status = SeatStatus::Busted;
+                }
+            }
+        }
+        self.current_bet = 0;
+        self.last_raise_size = 0;
+        self.raises_this_street = 0;
+        self.pot_total = 0;
+        self.pots.clear();
+        self.game_stage = GameStage::Waiting;
+        self.action_deadline = 0;
+        self.saw_flop = false;
+        self.board_disputed = false;
+        self.reset_shuffle();
+    }
+
+    /// Pass the action to the next player who can still act
+    fn advance_turn(&mut self) {
+        self.current_player_turn = self.next_actor(self.current_player_turn);
+    }
+
+    /// Move up to `amount` chips from a player's stack into the pot, putting
+    /// them all-in if that empties their stack. Returns the chips moved.
+    fn commit_chips(&mut self, player_index: usize, amount: u64) -> Result<u64, ProgramError> {
+        let player = self.player_mut(player_index);
+        let committed = amount.min(player.chips);
+
+        player.chips -= committed;
+        player.current_bet = player
+            .current_bet
+            .checked_add(committed)
+            .ok_or(PokerError::Overflow)?;
+        player.total_contribution = player
+            .total_contribution
+            .checked_add(committed)
+            .ok_or(PokerError::Overflow)?;
+        if player.chips == 0 {
+            player.is_all_in = true;
+            msg!("Player {} is all-in", player.name);
+        }
+
+        self.pot_total = self
+            .pot_total
+            .checked_add(committed)
+            .ok_or(PokerError::Overflow)?;
+
+        Ok(committed)
+    }
+
+    /// Everyone who can still act has had a chance to and matched the bet
+    pub fn is_betting_round_complete(&self) -> bool {
+        let actors: Vec<&Player> = self.seated().filter(|p| p.can_act()).collect();
+
+        // A lone player with nobody left to bet against only needs to have
+        // matched the all-in amounts
+        if actors.len() <= 1 {
+            return actors.iter().all(|p| p.current_bet >= self.current_bet);
+        }
+
+        actors
+            .iter()
+            .all(|p| p.has_acted && p.current_bet == self.current_bet)
+    }
+
+    /// Smallest bet, or raise over the current bet: the last full bet or
+    /// raise on the street and never less than the big blind, or the fixed
+    /// bet size in fixed limit
+    pub fn min_raise(&self) -> u64 {
+        match self.config.betting_structure {
+            BettingStructure::FixedLimit => self.limit_bet_size(),
+            _ => self.last_raise_size.max(self.config.big_blind),
+        }
+    }
+
+    /// Largest bet, or raise over the current bet, a player may make
+    pub fn max_raise(&self, player_index: usize) -> u64 {
+        match self.config.betting_structure {
+            BettingStructure::NoLimit => u64::MAX,
+            BettingStructure::PotLimit => {
+                let call_amount = self.current_bet - self.player(player_index).current_bet;
+                self.pot_total.saturating_add(call_amount)
+            }
+            BettingStructure::FixedLimit => self.limit_bet_size(),
+        }
+    }
+
+    /// Fixed limit bet size for the street: the small bet before the turn
+    /// and the big bet from it on
+    fn limit_bet_size(&self) -> u64 {
+        match self.game_stage {
+            GameStage::PreFlop | GameStage::Flop => self.config.big_blind,
+            _ => self.config.big_blind.saturating_mul(2),
+        }
+    }
+
+    /// Check a bet, or raise over the current bet, of `amount` stays within
+    /// the table's betting structure
+    fn check_betting_limit(&self, player_index: usize, amount: u64) -> ProgramResult {
+        if self.current_bet > 0
+            && self.config.betting_structure == BettingStructure::FixedLimit
+            && self.raises_this_street >= self.config.max_raises
+        {
+            msg!("Betting is capped at {} raises this street", self.config.max_raises);
+            return Err(PokerError::RaiseCapReached.into());
+        }
+
+        let max_raise = self.max_raise(player_index);
+        if amount > max_raise {
+            msg!("Bet or raise can be at most {}", self.ui_amount(max_raise));
+            return Err(PokerError::BetTooLarge.into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether a player may raise. Once they have acted on the street, only
+    /// a full raise's worth of bets since then, made up of one raise or
+    /// several short all-ins, reopens the betting to them.
+    fn may_raise(&self, player_index: usize) -> bool {
+        let player = self.player(player_index);
+        !player.has_acted || self.current_bet - player.current_bet >= self.min_raise()
+    }
+
+    /// Move to the next street, running the board out to showdown when
+    /// fewer than two players can still bet
+    fn advance_street(&mut self) {
+        loop {
+            self.game_stage = match self.game_stage {
+                GameStage::PreFlop => {
+                    self.saw_flop = true;
+                    GameStage::Flop
+                }
+                GameStage::Flop => GameStage::Turn,
+                GameStage::Turn => GameStage::River,
+                _ => GameStage::Showdown,
+            };
+
+            for player in self.seated_mut() {
+                player.current_bet = 0;
+                player.has_acted = false;
+            }
+            self.current_bet = 0;
+            self.last_raise_size = 0;
+            self.raises_this_street = 0;
+
+            msg!("Betting round complete, moving to {:?}", self.game_stage);
+
+            let actors = self.seated().filter(|p| p.can_act()).count();
+            if self.game_stage == GameStage::Showdown || actors >= 2 {
+                break;
+            }
+        }
+
+        if self.game_stage == GameStage::Showdown {
+            self.build_pots();
+        } else {
+            // Postflop action starts with the first player left of the dealer
+            self.current_player_turn = self.next_actor(self.dealer_index);
+        }
+    }
+
+    /// Hand the action on after a player acts, closing the street if the
+    /// betting round is complete
+    fn end_action(&mut self) {
+        let players_in_hand = self.seated().filter(|p| !p.has_folded).count();
+
+        if players_in_hand <= 1 {
+            // Everyone else folded; the hand is ready to be settled
+            self.game_stage = GameStage::Showdown;
+            self.build_pots();
+            msg!("Only one player left in the hand");
+        } else if self.is_betting_round_complete() {
+            self.advance_street();
+        } else {
+            self.advance_turn();
+        }
+    }
+
+    /// Take the house's share of the settled pots into `rake_accrued` and
+    /// return it. Only called chips are raked: a bet nobody matched goes
+    /// back whole. The rake comes out of the main pot first.
+    fn take_rake(&mut self) -> Result<u64, ProgramError> {
+        let config = self.config;
+        if config.rake_bps == 0 || (config.no_flop_no_drop && !self.saw_flop) {
+            return Ok(0);
+        }
+
+        let mut contributions: Vec<u64> = self.seated().map(|p| p.total_contribution).collect();
+        contributions.sort_unstable_by(|a, b| b.cmp(a));
+        let uncalled = match contributions.as_slice() {
+            [highest, next, ..] => highest - next,
+            _ => 0,
+        };
+        let called = self.pot_total - uncalled;
+
+        let rake = (called as u128 * config.rake_bps as u128 / 10_000) as u64;
+        let rake = rake.min(config.rake_cap()?);
+
+        let mut remaining = rake;
+        for pot in &mut self.pots {
+            let taken = remaining.min(pot.amount);
+            pot.amount -= taken;
+            remaining -= taken;
+        }
+        self.pot_total -= rake;
+        self.rake_accrued = self
+            .rake_accrued
+            .checked_add(rake)
+            .ok_or(PokerError::Overflow)?;
+
+        Ok(rake)
+    }
+
+    /// Work out what each seat wins from `pot_winners`, one list of winning
+    /// seats per pot. Chopped pots split evenly; odd chips go one at a time to
+    /// the winners closest to the left of the button.
+    pub fn pot_payouts(&self, pot_winners: &[Vec<u8>]) -> Result<Vec<SeatPayout>, ProgramError> {
+        if pot_winners.len() != self.pots.len() {
+            msg!(
+                "Expected winners for {} pots, got {}",
+                self.pots.len(),
+                pot_winners.len()
+            );
+            return Err(PokerError::PotWinnersMismatch.into());
+        }
+
+        let mut payouts: Vec<SeatPayout> = Vec::new();
+
+        for (pot, winners) in self.pots.iter().zip(pot_winners) {
+            let mut winners = winners.clone();
+            winners.sort_unstable_by_key(|&seat| self.seats_after_button(seat));
+
+            let duplicate = winners.windows(2).any(|pair| pair[0] == pair[1]);
+            let ineligible = winners
+                .iter()
+                .any(|seat| !pot.eligible_seats.contains(seat));
+            if winners.is_empty() || duplicate || ineligible {
+                msg!(
+                    "Invalid winners {:?} for pot contested by {:?}",
+                    winners,
+                    pot.eligible_seats
+                );
+                return Err(PokerError::InvalidWinner.into());
+            }
+
+            let share = pot.amount / winners.len() as u64;
+            let odd_chips = pot.amount % winners.len() as u64;
+
+            for (position, &seat) in winners.iter().enumerate() {
+                let amount = share + u64::from((position as u64) < odd_chips);
+                match payouts.iter_mut().find(|payout| payout.seat == seat) {
+                    Some(payout) => payout.amount += amount,
+                    None => payouts.push(SeatPayout { seat, amount }),
+                }
+            }
+        }
+
+        Ok(payouts)
+    }
+
+    /// Split the chips committed this hand into a main pot and side pots.
+    /// Each all-in amount caps a pot that only players who matched it can win.
+    fn build_pots(&mut self) {
+        let mut levels: Vec<u64> = self
+            .seated()
+            .filter(|p| !p.has_folded && p.total_contribution > 0)
+            .map(|p| p.total_contribution)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<SidePot> = Vec::new();
+        let mut previous_level = 0;
+        for level in levels {
+            let amount = self
+                .seated()
+                .map(|p| p.total_contribution.min(level) - p.total_contribution.min(previous_level))
+                .sum();
+            let eligible_seats: Vec<u8> = self
+                .seated()
+                .filter(|p| !p.has_folded && p.total_contribution >= level)
+                .map(|p| p.seat_index)
+                .collect();
+
+            // Levels with the same contenders belong to the same pot
+            match pots.last_mut() {
+                Some(pot) if pot.eligible_seats == eligible_seats => pot.amount += amount,
+                _ => pots.push(SidePot {
+                    amount,
+                    eligible_seats,
+                }),
+            }
+            previous_level = level;
+        }
+
+        // Dead money folded above the last live level stays in the last pot
+        let assigned: u64 = pots.iter().map(|pot| pot.amount).sum();
+        if let Some(pot) = pots.last_mut() {
+            pot.amount += self.pot_total - assigned;
+        }
+
+        for (index, pot) in pots.iter().enumerate() {
+            msg!(
+                "Pot {}: {}, eligible seats {:?}",
+                index,
+                self.ui_amount(pot.amount),
+                pot.eligible_seats
+            );
+        }
+
+        self.pots = pots;
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Player {
+    pub pubkey: Pubkey,
+    pub name: String,
+    pub chips: u64, // In the mint's base units
+    pub current_bet: u64,
+    pub has_folded: bool,
+    pub has_acted: bool, // Acted since the last bet or raise on this street
+    pub total_contribution: u64, // Chips committed across every street this hand
+    pub is_all_in: bool,
+    pub seed_commitment: Option<[u8; 32]>,
+    pub revealed_seed: Option<[u8; 32]>,
+    pub hole_index: Option<u8>, // Dealing order; holds deck cards 2i and 2i + 1
+    pub key_commitment: Option<[u8; 32]>,
+    pub unmask_step: u8,
+    pub decryption_key: Option<sra::Key>, // Revealed at showdown
+    pub hole_cards: Option<[u8; 2]>, // Shown at showdown
+    pub status: SeatStatus, // Whether the seat is dealt into the next hand
+    pub seat_index: u8, // Chosen at buy-in and kept until cash-out
+    pub missed_big_blind: bool, // The big blind passed the seat while sitting out
+    pub board_unmasks: Vec<sra::Point>, // Board cards as this player unmasked them
+}
+
+impl Player {
+    /// Serialized size with the longest allowed name
+    pub const SPACE: usize = 32 // pubkey
+        + 4 + MAX_NAME_LEN // name
+        + 8 + 8 // chips, current_bet
+        + 1 + 1 // has_folded, has_acted
+        + 8 + 1 // total_contribution, is_all_in
+        + 33 + 33 // seed_commitment, revealed_seed
+        + 2 + 33 + 1 // hole_index, key_commitment, unmask_step
+        + 33 + 3 // decryption_key, hole_cards
+        + 1 + 1 + 1 // status, seat_index, missed_big_blind
+        + 4 + BOARD_CARDS * 32; // board_unmasks
+
+    /// Dealt in and still in the hand with chips behind to bet
+    pub fn can_act(&self) -> bool {
+        self.hole_index.is_some() && !self.has_folded && !self.is_all_in
+    }
+
+    /// Has chips and is playing, not sitting out or leaving
+    pub fn wants_cards(&self) -> bool {
+        self.chips > 0 && self.status == SeatStatus::Playing
+    }
+}
+
+/// Whether a seated player is dealt into hands. Seats that are not
+/// `Playing` keep their chips and their seat but are skipped when dealing.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SeatStatus {
+    Playing,
+    /// Sat out by `SitOut` or a timeout until they sit back in
+    SittingOut,
+    /// Leaving once the hand they are in is over
+    PendingLeave,
+    /// Out of chips until they buy in again
+    Busted,
+}
+
+/// How much a player may bet or raise
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BettingStructure {
+    /// Up to the whole stack
+    NoLimit,
+    /// Up to the size of the pot once the player has called
+    PotLimit,
+    /// Exactly one small bet (the big blind) preflop and on the flop and one
+    /// big bet (two big blinds) on the turn and river, with a capped number
+    /// of raises per street
+    FixedLimit,
+}
+
+/// Seats the button and blinds fall on for a hand. The button and small
+/// blind can be dead: on a seat nobody was dealt in from.
+struct BlindPositions {
+    button: u8,
+    small_blind: u8,
+    big_blind: u8,
+}
+
+/// Chips a seat was awarded when a hand settled
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SeatPayout {
+    pub seat: u8,
+    pub amount: u64,
+}
+
+/// Emitted through `sol_log_data` when `EndRound` settles a hand
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SettlementEvent {
+    pub payouts: Vec<SeatPayout>,
+    pub rake: u64,
+}
+
+/// A pot and the seats that can win it. The first pot is the main pot;
+/// each later one is a side pot capped by an all-in.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SidePot {
+    pub amount: u64,
+    pub eligible_seats: Vec<u8>,
+}
+
+/// Stakes a table is created with. Buy-ins are counted in big blinds, so
+/// one config shape covers every stake level.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TableConfig {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: Option<u64>, // Posted as dead money by everyone dealt in
+    pub min_buy_in_bbs: u16,
+    pub max_buy_in_bbs: u16,
+    pub decimals: u8, // Of the table's mint
+    pub rake_bps: u16, // Share of each called pot the house keeps
+    pub rake_cap_bbs: u16, // Most rake per hand; 0 for no cap
+    pub no_flop_no_drop: bool, // Hands that end before the flop are not raked
+    pub dead_button: bool, // Big blind moves one seat a hand; otherwise the button does
+    pub betting_structure: BettingStructure,
+    pub max_raises: u8, // Raises per street after the opening bet, in fixed limit
+}
+
+impl TableConfig {
+    pub const SPACE: usize = 8 + 8 + 1 + 8 + 2 + 2 + 1 + 2 + 2 + 1 + 1 + 1 + 1;
+
+    /// Check the stakes make sense for a table on a mint with
+    /// `mint_decimals`
+    pub fn validate(&self, mint_decimals: u8) -> ProgramResult {
+        if self.small_blind == 0 || self.big_blind < self.small_blind {
+            msg!(
+                "Blinds {}/{} must be nonzero with the big blind at least the small",
+                self.small_blind,
+                self.big_blind
+            );
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.ante.is_some_and(|ante| ante == 0 || ante > self.big_blind) {
+            msg!("Ante must be nonzero and at most the big blind");
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.min_buy_in_bbs == 0 || self.max_buy_in_bbs < self.min_buy_in_bbs {
+            msg!(
+                "Buy-in range of {} to {} big blinds is empty",
+                self.min_buy_in_bbs,
+                self.max_buy_in_bbs
+            );
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.max_buy_in().is_err() {
+            msg!("Maximum buy-in overflows");
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.rake_bps > MAX_RAKE_BPS || self.rake_cap().is_err() {
+            msg!(
+                "Rake of {} bps capped at {} big blinds is out of range",
+                self.rake_bps,
+                self.rake_cap_bbs
+            );
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.betting_structure == BettingStructure::FixedLimit && self.max_raises == 0 {
+            msg!("Fixed limit needs at least one raise per street");
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        if self.decimals != mint_decimals {
+            msg!(
+                "Config is for {} decimals but the mint has {}",
+                self.decimals,
+                mint_decimals
+            );
+            return Err(PokerError::InvalidTableConfig.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn min_buy_in(&self) -> Result<u64, ProgramError> {
+        self.big_blind
+            .checked_mul(self.min_buy_in_bbs as u64)
+            .ok_or_else(|| PokerError::Overflow.into())
+    }
+
+    pub fn max_buy_in(&self) -> Result<u64, ProgramError> {
+        self.big_blind
+            .checked_mul(self.max_buy_in_bbs as u64)
+            .ok_or_else(|| PokerError::Overflow.into())
+    }
+
+    /// Most rake a hand can pay
+    pub fn rake_cap(&self) -> Result<u64, ProgramError> {
+        if self.rake_cap_bbs == 0 {
+            return Ok(u64::MAX);
+        }
+        self.big_blind
+            .checked_mul(self.rake_cap_bbs as u64)
+            .ok_or_else(|| PokerError::Overflow.into())
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum GameStage {
+    Waiting,
+    PreFlop,
+    Flop,
+    Turn,
+    River,
+    Showdown,
+}
+
+// Ensure the vault is the table's pot account and still backs every chip.
+// Funds sent straight to the vault back nothing and are left alone.
+fn check_vault_balance(game_state: &GameState, pot_token_account: &AccountInfo) -> ProgramResult {
+    if *pot_token_account.key != game_state.pot_token_account {
+        msg!("Invalid pot token account");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+
+    let vault_balance = game_state.custody.balance(pot_token_account)?;
+    let chips_in_play = game_state.chips_in_play()?;
+
+    if vault_balance < chips_in_play {
+        msg!(
+            "Vault holds {} but {} chips are in play",
+            vault_balance,
+            chips_in_play
+        );
+        return Err(PokerError::VaultBalanceMismatch.into());
+    }
+
+    Ok(())
+}
+
+// Initialize game
+fn initialize_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_players: u8,
+    table_id: u64,
+    config: TableConfig,
+) -> ProgramResult {
+    let InitializeGameAccounts {
+        authority,
+        game_state: game_state_account,
+        mint,
+        pot_token_account,
+        pot_authority,
+        token_program,
+        associated_token_program,
+        system_program: system_program_account,
+        rent_sysvar,
+    } = InitializeGameAccounts::load(&mut accounts.iter())?;
+
+    config.validate(token::unpack_mint(mint)?.decimals)?;
+    let rent = Rent::from_account_info(rent_sysvar)?;
+    let game_bump = create_game_state(
+        program_id,
+        authority,
+        game_state_account,
+        system_program_account,
+        &rent,
+        max_players,
+        table_id,
+    )?;
+
+    // Derive the pot authority PDA; the vault is its token account for the mint
+    let (pot_pda, pot_bump) = Pubkey::find_program_address(
+        &[POT_SEED, game_state_account.key.as_ref()],
+        program_id,
+    );
+
+    if pot_pda != *pot_authority.key {
+        msg!("Invalid pot authority");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+
+    let vault = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &pot_pda,
+        mint.key,
+        token_program.key,
+    );
+    if vault != *pot_token_account.key {
+        msg!("Invalid pot token account");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+
+    // Create the vault. Anyone can create an associated token account, so
+    // one created ahead of the table is taken as it is.
+    invoke(
+        &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            authority.key,
+            &pot_pda,
+            mint.key,
+            token_program.key,
+        ),
+        &[
+            authority.clone(),
+            pot_token_account.clone(),
+            pot_authority.clone(),
+            mint.clone(),
+            system_program_account.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )?;
+
+    let vault_account = token::unpack_account(pot_token_account)?;
+    if vault_account.mint != *mint.key || vault_account.owner != pot_pda {
+        msg!("Vault is not the pot authority's token account");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+
+    let game_state = GameState {
+        mint: *mint.key,
+        pot_token_account: *pot_token_account.key,
+        pot_bump,
+        pot_authority: pot_pda,
+        token_program: *token_program.key,
+        custody: Custody::Token,
+        ..GameState::new(*authority.key, table_id, game_bump, max_players, config)
+    };
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Game {} initialized with max {} players, blinds: {}/{}",
+        table_id,
+        max_players,
+        game_state.ui_amount(config.small_blind),
+        game_state.ui_amount(config.big_blind)
+    );
+    Ok(())
+}
+
+// Initialize a native SOL game
+fn initialize_native_game(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_players: u8,
+    table_id: u64,
+    config: TableConfig,
+) -> ProgramResult {
+    let InitializeNativeGameAccounts {
+        authority,
+        game_state: game_state_account,
+        escrow,
+        system_program: system_program_account,
+        rent_sysvar,
+    } = InitializeNativeGameAccounts::load(&mut accounts.iter())?;
+
+    config.validate(spl_token::native_mint::DECIMALS)?;
+    let rent = Rent::from_account_info(rent_sysvar)?;
+    let game_bump = create_game_state(
+        program_id,
+        authority,
+        game_state_account,
+        system_program_account,
+        &rent,
+        max_players,
+        table_id,
+    )?;
+
+    // The escrow is the pot authority PDA, owned by the program so payouts
+    // can debit it directly
+    let (pot_pda, pot_bump) = Pubkey::find_program_address(
+        &[POT_SEED, game_state_account.key.as_ref()],
+        program_id,
+    );
+
+    if pot_pda != *escrow.key {
+        msg!("Invalid escrow account");
+        return Err(PokerError::InvalidPotAccount.into());
+    }
+
+    create_pda_account(
+        authority,
+        escrow,
+        system_program_account,
+        &rent,
+        0,
+        program_id,
+        &[POT_SEED, game_state_account.key.as_ref(), &[pot_bump]],
+    )?;
+
+    let game_state = GameState {
+        mint: spl_token::native_mint::id(),
+        pot_token_account: pot_pda,
+        pot_bump,
+        pot_authority: pot_pda,
+        token_program: system_program::id(),
+        custody: Custody::Lamports,
+        ..GameState::new(*authority.key, table_id, game_bump, max_players, config)
+    };
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Native game {} initialized with max {} players, blinds: {}/{} SOL",
+        table_id,
+        max_players,
+        game_state.ui_amount(config.small_blind),
+        game_state.ui_amount(config.big_blind)
+    );
+    Ok(())
+}
+
+// Create the game state account at the table's PDA, sized for a full table,
+// and return its bump
+fn create_game_state<'info>(
+    program_id: &Pubkey,
+    authority: &AccountInfo<'info>,
+    game_state_account: &AccountInfo<'info>,
+    system_program_account: &AccountInfo<'info>,
+    rent: &Rent,
+    max_players: u8,
+    table_id: u64,
+) -> Result<u8, ProgramError> {
+    if !(2..=MAX_PLAYERS).contains(&max_players) {
+        msg!("Tables seat 2 to {} players", MAX_PLAYERS);
+        return Err(PokerError::InvalidMaxPlayers.into());
+    }
+
+    // Derive game state PDA
+    let table_id_bytes = table_id.to_le_bytes();
+    let (game_pda, game_bump) = Pubkey::find_program_address(
+        &[GAME_SEED, authority.key.as_ref(), &table_id_bytes],
+        program_id,
+    );
+
+    if game_pda != *game_state_account.key {
+        msg!("Invalid game state account");
+        return Err(PokerError::InvalidGameAccount.into());
+    }
+
+    if game_state_account.owner == program_id {
+        msg!("Table {} already exists", table_id);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    // Create the game state account, sized for a full table
+    create_pda_account(
+        authority,
+        game_state_account,
+        system_program_account,
+        rent,
+        GameState::space(max_players),
+        program_id,
+        &[GAME_SEED, authority.key.as_ref(), &table_id_bytes, &[game_bump]],
+    )?;
+
+    Ok(game_bump)
+}
+
+// Create an account owned by `owner` at the PDA signed for by `seeds`.
+// Anyone can send lamports to the address first, which makes
+// `create_account` fail, so an address that already holds some is topped
+// up to rent exemption, allocated and assigned instead.
+fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program_account: &AccountInfo<'info>,
+    rent: &Rent,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    let required = rent.minimum_balance(space);
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                required,
+                space as u64,
+                owner,
+            ),
+            &[payer.clone(), account.clone(), system_program_account.clone()],
+            &[seeds],
+        );
+    }
+
+    let top_up = required.saturating_sub(account.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, top_up),
+            &[payer.clone(), account.clone(), system_program_account.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program_account.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        &[account.clone(), system_program_account.clone()],
+        &[seeds],
+    )
+}
+
+// Buy in to game
+fn buy_in(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    seat: Option<u8>,
+) -> ProgramResult {
+    let accounts = BuyInAccounts::load(program_id, &mut accounts.iter())?;
+    let BuyInAccounts {
+        player: player_account,
+        game_state: game_state_account,
+        player_token_account,
+        pot_token_account,
+        mint,
+        token_program,
+    } = accounts;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
+
+    let config = game_state.config;
+
+    // Check if player already joined
+    let player_index = game_state.seat_of(player_account.key).ok();
+
+    // A top-up only has to keep the stack within the maximum; a new stack
+    // has to be within the whole buy-in range
+    let stack = match player_index {
+        Some(idx) => game_state
+            .player(idx)
+            .chips
+            .checked_add(amount)
+            .ok_or(PokerError::InvalidBuyIn)?,
+        None => amount,
+    };
+    if stack > config.max_buy_in()? {
+        msg!("Stack would exceed the maximum buy-in of {} big blinds", config.max_buy_in_bbs);
+        return Err(PokerError::InvalidBuyIn.into());
+    }
+
+    if player_index.is_none() && amount < config.min_buy_in()? {
+        msg!("Buy-in must be at least {} big blinds", config.min_buy_in_bbs);
+        return Err(PokerError::InvalidBuyIn.into());
+    }
+
+    if let Some(idx) = player_index {
+        if seat.is_some_and(|seat| seat as usize != idx) {
+            msg!("Player is already in seat {}", idx);
+            return Err(PokerError::InvalidSeat.into());
+        }
+
+        // Chips can't be added to a stack that may be in the pot
+        if game_state.game_stage != GameStage::Waiting {
+            msg!("Can only top up between hands");
+            return Err(PokerError::HandInProgress.into());
+        }
+
+        // Player exists, add to their chips and deal them back in
+        let player = game_state.player_mut(idx);
+        player.chips = stack;
+        player.status = SeatStatus::Playing;
+        msg!("Player topped up {}. New balance: {}",
+            game_state.ui_amount(amount),
+            game_state.ui_amount(game_state.player(idx).chips)
+        );
+    } else {
+        // Check if game is full
+        if game_state.current_players >= game_state.max_players {
+            msg!("Game is full");
+            return Err(PokerError::GameFull.into());
+        }
+
+        let seat_index = match seat {
+            Some(seat) if seat >= game_state.max_players => {
+                msg!("Table only has {} seats", game_state.max_players);
+                return Err(PokerError::InvalidSeat.into());
+            }
+            Some(seat) if game_state.seat(seat as usize).is_some() => {
+                msg!("Seat {} is taken", seat);
+                return Err(PokerError::SeatTaken.into());
+            }
+            Some(seat) => seat,
+            None => game_state
+                .players
+                .iter()
+                .position(Option::is_none)
+                .ok_or(PokerError::GameFull)? as u8,
+        };
+
+        // New player
+        let player_name = format!("Player_{}", seat_index + 1);
+        let new_player = Player {
+            pubkey: *player_account.key,
+            name: player_name.clone(),
+            chips: amount,
+            current_bet: 0,
+            has_folded: true, // Out of any hand under way until dealt in
+            has_acted: false,
+            total_contribution: 0,
+            is_all_in: false,
+            seed_commitment: None,
+            revealed_seed: None,
+            hole_index: None,
+            key_commitment: None,
+            unmask_step: 0,
+            decryption_key: None,
+            hole_cards: None,
+            status: SeatStatus::Playing,
+            seat_index,
+            missed_big_blind: false,
+            board_unmasks: Vec::new(),
+        };
+
+        game_state.players[seat_index as usize] = Some(new_player);
+        game_state.current_players += 1;
+
+        msg!(
+            "Player {} took seat {} for {}",
+            player_name,
+            seat_index,
+            game_state.ui_amount(amount)
+        );
+    }
+
+    // Move the buy-in into the vault
+    let transfer = VaultTransfer {
+        vault: pot_token_account,
+        funds: player_token_account,
+        mint,
+        program: token_program,
+        decimals: game_state.config.decimals,
+    };
+    game_state.custody.deposit(&transfer, player_account, amount)?;
+
+    check_vault_balance(&game_state, pot_token_account)?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Start round with automatic blinds
+fn start_round(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let accounts = AuthorityAccounts::load(program_id, accounts_iter)?;
+    let game_state_account = accounts.game_state;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
+
+    if game_state.game_stage != GameStage::Waiting {
+        msg!("Previous hand has not been settled");
+        return Err(PokerError::HandInProgress.into());
+    }
+
+    // Players who busted out or were sat out are not dealt in
+    let players_with_chips = game_state.seated().filter(|p| p.wants_cards()).count();
+    if players_with_chips < 2 {
+        msg!("Need at least 2 players to start");
+        return Err(PokerError::NotEnoughPlayers.into());
+    }
+
+    // Oracle tables deal in everyone with chips; otherwise wait for every
+    // seed until the reveal deadline passes
+    let oracle_randomness = match game_state.randomness_oracle {
+        Some(oracle) => {
+            let randomness_account = next_account_info(accounts_iter)?;
+            let current_slot = Clock::get()?.slot;
+            Some(OracleRandomness::load(
+                randomness_account,
+                &oracle,
+                game_state.last_randomness_slot,
+                current_slot,
+            )?)
+        }
+        None => None,
+    };
+
+    if oracle_randomness.is_none() {
+        let now = Clock::get()?.unix_timestamp;
+        let unrevealed = game_state
+            .seated()
+            .filter(|p| p.wants_cards() && p.revealed_seed.is_none())
+            .count();
+        if unrevealed > 0 && (game_state.commit_deadline == 0 || now <= game_state.reveal_deadline) {
+            msg!("Waiting on {} seed reveals", unrevealed);
+            return Err(PokerError::ShuffleIncomplete.into());
+        }
+
+        let revealers: Vec<u8> = game_state
+            .seated()
+            .filter(|p| p.wants_cards() && p.revealed_seed.is_some())
+            .map(|p| p.seat_index)
+            .collect();
+        if revealers.len() < 2 {
+            msg!("Too few seeds were revealed to deal; restarting the shuffle");
+
+            // With no hand to put it in, a withheld reveal's big blind goes
+            // straight to whoever did reveal
+            if !revealers.is_empty() {
+                let big_blind = game_state.config.big_blind;
+                let mut penalties: u64 = 0;
+                for seat in game_state.withheld_seeds() {
+                    let player = game_state.player_mut(seat);
+                    let forfeited = player.chips.min(big_blind);
+                    player.chips -= forfeited;
+                    penalties = penalties.checked_add(forfeited).ok_or(PokerError::Overflow)?;
+                    msg!(
+                        "Player {} forfeited {} for not revealing their seed",
+                        game_state.player(seat).name,
+                        game_state.ui_amount(forfeited)
+                    );
+                }
+                game_state.share_out(revealers, penalties)?;
+            }
+
+            game_state.sit_out_unrevealed();
+            game_state.reset_shuffle();
+            game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+            return Ok(());
+        }
+    }
+
+    // Reset hand state; at seeded tables players who missed the reveal are
+    // sat out so later hands don't wait on them, and posting a blind does
+    // not count as acting
+    if oracle_randomness.is_none() {
+        game_state.sit_out_unrevealed();
+    }
+    for player in game_state.seated_mut() {
+        player.has_folded = !player.wants_cards();
+        player.has_acted = false;
+        player.current_bet = 0;
+        player.total_contribution = 0;
+        player.is_all_in = false;
+    }
+    game_state.pot_total = 0;
+    game_state.pots.clear();
+
+    // Withholding a reveal after seeing everyone else's seed could bias the
+    // deck, so it costs a big blind of dead money
+    let big_blind = game_state.config.big_blind;
+    for seat in game_state.withheld_seeds() {
+        let forfeited = game_state.commit_chips(seat, big_blind)?;
+        game_state.player_mut(seat).current_bet = 0;
+        msg!(
+            "Player {} forfeited {} for not revealing their seed",
+            game_state.player(seat).name,
+            game_state.ui_amount(forfeited)
+        );
+    }
+
+    // The seed fixes the order the deck enters the encryption passes in;
+    // dealt-in players then encrypt and reshuffle it in seat order
+    let deck_seed = match &oracle_randomness {
+        Some(randomness) => {
+            game_state.last_randomness_slot = randomness.fulfilled_slot();
+            randomness.deck_seed()?
+        }
+        None => PlayerSeeds::new(&game_state.players).deck_seed()?,
+    };
+    game_state.deck = shuffle::deck_from_seed(&deck_seed)
+        .into_iter()
+        .map(sra::encode)
+        .collect();
+    game_state.board.clear();
+
+    let mut dealt_in = 0;
+    for player in game_state.seated_mut() {
+        player.hole_cards = None;
+        player.hole_index = if player.has_folded {
+            None
+        } else {
+            dealt_in += 1;
+            Some(dealt_in - 1)
+        };
+    }
+
+    // Antes are dead money: they go in the pot but do not count towards
+    // anyone's bet on the street
+    if let Some(ante) = game_state.config.ante {
+        let dealt_in: Vec<usize> = game_state
+            .seated()
+            .filter(|p| !p.has_folded)
+            .map(|p| p.seat_index as usize)
+            .collect();
+        for seat in dealt_in {
+            game_state.commit_chips(seat, ante)?;
+            game_state.player_mut(seat).current_bet = 0;
+        }
+    }
+
+    let BlindPositions {
+        button,
+        small_blind: small_blind_idx,
+        big_blind: big_blind_idx,
+    } = game_state.blind_positions();
+    game_state.dealer_index = button;
+
+    // A player who cannot cover a blind posts what they have and is all-in
+    let small_blind = game_state.config.small_blind;
+    let small_blind_dead = game_state
+        .seat(small_blind_idx as usize)
+        .is_none_or(|p| p.hole_index.is_none());
+    if !small_blind_dead {
+        game_state.commit_chips(small_blind_idx as usize, small_blind)?;
+    }
+    game_state.commit_chips(big_blind_idx as usize, big_blind)?;
+    game_state.track_missed_blinds(big_blind_idx);
+
+    // Players back from missing the big blind post one before they play.
+    // It is live, so a small blind only tops up to it and the big blind
+    // has already paid.
+    let owing: Vec<usize> = game_state
+        .seated()
+        .filter(|p| p.missed_big_blind && p.hole_index.is_some())
+        .map(|p| p.seat_index as usize)
+        .collect();
+    for seat in owing {
+        let owed = big_blind.saturating_sub(game_state.player(seat).current_bet);
+        let posted = game_state.commit_chips(seat, owed)?;
+        game_state.player_mut(seat).missed_big_blind = false;
+        msg!(
+            "Player {} posted {} for the missed big blind",
+            game_state.player(seat).name,
+            game_state.ui_amount(posted)
+        );
+    }
+
+    game_state.current_bet = big_blind;
+    game_state.last_raise_size = big_blind;
+    game_state.raises_this_street = 0;
+    game_state.game_stage = GameStage::PreFlop;
+    game_state.current_player_turn = big_blind_idx;
+
+    if game_state.is_betting_round_complete() {
+        // Blinds put everyone all-in; nobody is left to act
+        game_state.advance_street();
+    } else {
+        game_state.advance_turn();
+    }
+    game_state.restart_action_clock()?;
+
+    if small_blind_dead {
+        msg!(
+            "Round started. Button: seat {}, SB dead, BB={} (seat {})",
+            button,
+            game_state.ui_amount(big_blind),
+            big_blind_idx
+        );
+    } else {
+        msg!(
+            "Round started. Button: seat {}, SB={} (seat {}), BB={} (seat {})",
+            button,
+            game_state.ui_amount(small_blind),
+            small_blind_idx,
+            game_state.ui_amount(big_blind),
+            big_blind_idx
+        );
+    }
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Fold
+fn fold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.acting_player_index(player_account.key)?;
+
+    game_state.player_mut(player_index).has_folded = true;
+
+    msg!("Player {} folded", game_state.player(player_index).name);
+
+    game_state.end_action();
+    game_state.restart_action_clock()?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Call
+fn call(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.acting_player_index(player_account.key)?;
+
+    let call_amount = game_state.current_bet - game_state.player(player_index).current_bet;
+
+    if call_amount == 0 {
+        msg!("Nothing to call; check instead");
+        return Err(PokerError::NothingToCall.into());
+    }
+
+    // Calling short puts the player all-in for what they have
+    let called = game_state.commit_chips(player_index, call_amount)?;
+    game_state.player_mut(player_index).has_acted = true;
+
+    msg!(
+        "Player {} called with {}",
+        game_state.player(player_index).name,
+        game_state.ui_amount(called)
+    );
+
+    game_state.end_action();
+    game_state.restart_action_clock()?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
 }
 
-// Initialize game
-fn initialize_game(
+// Raise
+fn raise(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    max_players: u8,
+    raise_amount: u64,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let authority = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let usdc_mint = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let _token_program = next_account_info(accounts_iter)?;
-    let _system_program = next_account_info(accounts_iter)?;
-    let _rent = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
 
-    if !authority.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.acting_player_index(player_account.key)?;
+
+    if game_state.current_bet == 0 {
+        msg!("No bet to raise; bet instead");
+        return Err(PokerError::NoBetToRaise.into());
     }
 
-    // Derive pot PDA
-    let (pot_pda, pot_bump) = Pubkey::find_program_address(
-        &[POT_SEED, game_state_account.key.as_ref()],
-        program_id,
-    );
+    let call_amount = game_state.current_bet - game_state.player(player_index).current_bet;
+    let total_amount = call_amount
+        .checked_add(raise_amount)
+        .ok_or(PokerError::InsufficientChips)?;
 
-    if pot_pda != *pot_token_account.key {
-        msg!("Invalid pot token account");
-        return Err(ProgramError::InvalidAccountData);
+    if total_amount > game_state.player(player_index).chips {
+        msg!("Insufficient chips to raise");
+        return Err(PokerError::InsufficientChips.into());
     }
 
-    let game_state = GameState {
-        authority: *authority.key,
-        usdc_mint: *usdc_mint.key,
-        pot_token_account: *pot_token_account.key,
-        pot_bump,
-        max_players,
-        current_players: 0,
-        players: Vec::new(),
-        current_bet: 0,
-        pot_total: 0,
-        game_stage: GameStage::Waiting,
-        current_player_turn: 0,
-        dealer_index: 0,
-        small_blind: SMALL_BLIND,
-        big_blind: BIG_BLIND,
-    };
+    if !game_state.may_raise(player_index) {
+        msg!("A short all-in has not reopened the betting; call or fold");
+        return Err(PokerError::BettingNotReopened.into());
+    }
 
-    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+    // A raise must be at least as big as the last bet or raise
+    let min_raise = game_state.min_raise();
+    if raise_amount < min_raise {
+        msg!("Raise must be by at least {}", game_state.ui_amount(min_raise));
+        return Err(PokerError::RaiseTooSmall.into());
+    }
+    game_state.check_betting_limit(player_index, raise_amount)?;
+
+    // Everyone else gets a chance to respond to the raise
+    for player in game_state.seated_mut() {
+        player.has_acted = player.seat_index as usize == player_index;
+    }
+
+    game_state.commit_chips(player_index, total_amount)?;
+    game_state.current_bet += raise_amount;
+    game_state.last_raise_size = raise_amount;
+    game_state.raises_this_street += 1;
 
     msg!(
-        "Game initialized with max {} players, blinds: {}/{} USDC",
-        max_players,
-        SMALL_BLIND as f64 / 1_000_000.0,
-        BIG_BLIND as f64 / 1_000_000.0
+        "Player {} raised by {} (total bet: {})",
+        game_state.player(player_index).name,
+        game_state.ui_amount(raise_amount),
+        game_state.ui_amount(game_state.current_bet)
     );
+
+    game_state.end_action();
+    game_state.restart_action_clock()?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
     Ok(())
 }
 
-// Buy in to game
-fn buy_in(
-    _program_id: &Pubkey,
+// Check
+fn check(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    amount: u64,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let player_account = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let player_token_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
 
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    // Validate buy-in amount (max $5)
-    if amount > MAX_BUY_IN {
-        msg!("Buy-in exceeds maximum of $5 USDC");
-        return Err(ProgramError::InvalidArgument);
-    }
+    let player_index = game_state.acting_player_index(player_account.key)?;
 
-    if amount < BIG_BLIND * 10 {
-        msg!("Buy-in must be at least 10x the big blind");
-        return Err(ProgramError::InvalidArgument);
+    if game_state.player(player_index).current_bet < game_state.current_bet {
+        msg!("Cannot check facing a bet");
+        return Err(PokerError::CannotCheck.into());
     }
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    game_state.player_mut(player_index).has_acted = true;
 
-    // Check if game is full
-    if game_state.current_players >= game_state.max_players {
-        msg!("Game is full");
-        return Err(ProgramError::Custom(1));
-    }
+    msg!("Player {} checked", game_state.player(player_index).name);
 
-    // Check if player already joined
-    let player_index = game_state
-        .players
-        .iter()
-        .position(|p| p.pubkey == *player_account.key);
+    game_state.end_action();
+    game_state.restart_action_clock()?;
 
-    if let Some(idx) = player_index {
-        // Player exists, add to their chips
-        game_state.players[idx].chips += amount;
-        msg!("Player topped up {} USDC. New balance: {} USDC",
-            amount as f64 / 1_000_000.0,
-            game_state.players[idx].chips as f64 / 1_000_000.0
-        );
-    } else {
-        // New player
-        let player_name = format!("Player_{}", game_state.current_players + 1);
-        let new_player = Player {
-            pubkey: *player_account.key,
-            name: player_name.clone(),
-            chips: amount,
-            current_bet: 0,
-            is_active: true,
-            has_folded: false,
-        };
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
-        game_state.players.push(new_player);
-        game_state.current_players += 1;
+    Ok(())
+}
 
-        msg!("Player {} bought in for {} USDC", player_name, amount as f64 / 1_000_000.0);
+// Bet
+fn bet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.acting_player_index(player_account.key)?;
+
+    if game_state.current_bet > 0 {
+        msg!("A bet has already been made; raise instead");
+        return Err(PokerError::BetAlreadyMade.into());
     }
 
-    // Transfer USDC from player to pot
-    let transfer_ix = spl_token::instruction::transfer(
-        token_program.key,
-        player_token_account.key,
-        pot_token_account.key,
-        player_account.key,
-        &[],
-        amount,
-    )?;
+    // Minimum bet is the big blind, or the fixed bet size in fixed limit
+    let min_bet = game_state.min_raise();
+    if amount < min_bet {
+        msg!("Bet amount must be at least {}", game_state.ui_amount(min_bet));
+        return Err(PokerError::BetTooSmall.into());
+    }
+    game_state.check_betting_limit(player_index, amount)?;
 
-    invoke(
-        &transfer_ix,
-        &[
-            player_token_account.clone(),
-            pot_token_account.clone(),
-            player_account.clone(),
-            token_program.clone(),
-        ],
-    )?;
+    if amount > game_state.player(player_index).chips {
+        msg!("Insufficient chips to bet");
+        return Err(PokerError::InsufficientChips.into());
+    }
+
+    // Everyone else gets a chance to respond to the bet
+    for player in game_state.seated_mut() {
+        player.has_acted = player.seat_index as usize == player_index;
+    }
+
+    game_state.commit_chips(player_index, amount)?;
+    game_state.current_bet = amount;
+    game_state.last_raise_size = amount;
+
+    msg!(
+        "Player {} bet {}",
+        game_state.player(player_index).name,
+        game_state.ui_amount(amount)
+    );
+
+    game_state.end_action();
+    game_state.restart_action_clock()?;
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-// Start round with automatic blinds
-fn start_round(
+// All-in
+fn all_in(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let authority = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let small_blind_token_account = next_account_info(accounts_iter)?;
-    let big_blind_token_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
 
-    if !authority.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    let player_index = game_state.acting_player_index(player_account.key)?;
 
-    if game_state.authority != *authority.key {
-        return Err(ProgramError::Custom(4)); // Not authorized
+    let stack = game_state.player(player_index).chips;
+    let player_bet = game_state.player(player_index).current_bet + stack;
+    if player_bet > game_state.current_bet {
+        if !game_state.may_raise(player_index) {
+            msg!("A short all-in has not reopened the betting; call or fold");
+            return Err(PokerError::BettingNotReopened.into());
+        }
+        game_state.check_betting_limit(player_index, player_bet - game_state.current_bet)?;
     }
 
-    if game_state.current_players < 2 {
-        msg!("Need at least 2 players to start");
-        return Err(ProgramError::Custom(8));
+    game_state.commit_chips(player_index, stack)?;
+    game_state.player_mut(player_index).has_acted = true;
+
+    // Going all-in above the current bet is a bet or raise everyone else has
+    // to respond to. Only a full one reopens the betting to players who have
+    // already acted; a short one leaves them to call or fold.
+    if player_bet > game_state.current_bet {
+        let raise_amount = player_bet - game_state.current_bet;
+        if raise_amount >= game_state.min_raise() {
+            for player in game_state.seated_mut() {
+                player.has_acted = player.seat_index as usize == player_index;
+            }
+            game_state.last_raise_size = raise_amount;
+            if game_state.current_bet > 0 {
+                game_state.raises_this_street += 1;
+            }
+        }
+        game_state.current_bet = player_bet;
     }
 
-    // Calculate blind positions (relative to dealer)
-    let small_blind_idx = (game_state.dealer_index + 1) % game_state.current_players;
-    let big_blind_idx = (game_state.dealer_index + 2) % game_state.current_players;
+    msg!(
+        "Player {} went all-in for {} (total bet: {})",
+        game_state.player(player_index).name,
+        game_state.ui_amount(stack),
+        game_state.ui_amount(player_bet)
+    );
+
+    game_state.end_action();
+    game_state.restart_action_clock()?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Commit to a shuffle seed for the next hand
+fn commit_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    commitment: [u8; 32],
+) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
 
-    let small_blind_player = &mut game_state.players[small_blind_idx as usize];
-    let big_blind_player = &mut game_state.players[big_blind_idx as usize];
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    // Post small blind
-    if small_blind_player.chips < game_state.small_blind {
-        msg!("Small blind player doesn't have enough chips");
-        return Err(ProgramError::InsufficientFunds);
+    if game_state.game_stage != GameStage::Waiting {
+        msg!("Seeds are committed between hands");
+        return Err(PokerError::HandInProgress.into());
     }
 
-    // Post big blind
-    if big_blind_player.chips < game_state.big_blind {
-        msg!("Big blind player doesn't have enough chips");
-        return Err(ProgramError::InsufficientFunds);
+    if game_state.randomness_oracle.is_some() {
+        msg!("This table seeds its decks from an oracle");
+        return Err(PokerError::PlayerSeedsDisabled.into());
     }
 
-    // Update player states
-    small_blind_player.chips -= game_state.small_blind;
-    small_blind_player.current_bet = game_state.small_blind;
+    let player_index = game_state.seat_of(player_account.key)?;
 
-    big_blind_player.chips -= game_state.big_blind;
-    big_blind_player.current_bet = game_state.big_blind;
+    let now = Clock::get()?.unix_timestamp;
+    if game_state.reveals_open(now) {
+        msg!("Commit window has closed");
+        return Err(PokerError::CommitPhaseClosed.into());
+    }
 
-    game_state.current_bet = game_state.big_blind;
-    game_state.pot_total = game_state.small_blind + game_state.big_blind;
-    game_state.game_stage = GameStage::PreFlop;
-    game_state.current_player_turn = (big_blind_idx + 1) % game_state.current_players;
+    if game_state.player(player_index).seed_commitment.is_some() {
+        msg!("Seed already committed");
+        return Err(PokerError::SeedAlreadyCommitted.into());
+    }
+
+    // The first commitment opens the shuffle and starts both windows
+    if game_state.commit_deadline == 0 {
+        game_state.commit_deadline = now + SHUFFLE_PHASE_SECS;
+        game_state.reveal_deadline = now + 2 * SHUFFLE_PHASE_SECS;
+    }
 
-    // Reset folded status
-    for player in &mut game_state.players {
-        player.has_folded = false;
+    // Committing a seed sits a player back in
+    let player = game_state.player_mut(player_index);
+    player.seed_commitment = Some(commitment);
+    if player.status == SeatStatus::SittingOut {
+        player.status = SeatStatus::Playing;
     }
 
     msg!(
-        "Round started. Blinds posted: SB={} USDC, BB={} USDC",
-        game_state.small_blind as f64 / 1_000_000.0,
-        game_state.big_blind as f64 / 1_000_000.0
+        "Player {} committed a shuffle seed",
+        game_state.player(player_index).name
     );
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
@@ -409,277 +2634,464 @@ fn start_round(
     Ok(())
 }
 
-// Fold
-fn fold(
-    _program_id: &Pubkey,
+// Reveal a committed shuffle seed
+fn reveal_seed(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
+    seed: [u8; 32],
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let player_account = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if game_state.game_stage != GameStage::Waiting {
+        msg!("Seeds are revealed between hands");
+        return Err(PokerError::HandInProgress.into());
     }
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    let player_index = game_state.seat_of(player_account.key)?;
 
-    let player_index = game_state
-        .players
-        .iter()
-        .position(|p| p.pubkey == *player_account.key)
-        .ok_or(ProgramError::Custom(3))?;
+    let now = Clock::get()?.unix_timestamp;
+    if !game_state.reveals_open(now) || now > game_state.reveal_deadline {
+        msg!("Reveal window is not open");
+        return Err(PokerError::RevealPhaseNotOpen.into());
+    }
 
-    game_state.players[player_index].has_folded = true;
-    game_state.players[player_index].is_active = false;
+    let player = game_state.player_mut(player_index);
+    if player.seed_commitment != Some(shuffle::commitment(player_account.key, &seed)) {
+        msg!("Seed does not match commitment");
+        return Err(PokerError::InvalidSeedReveal.into());
+    }
 
-    msg!("Player {} folded", game_state.players[player_index].name);
+    player.revealed_seed = Some(seed);
 
-    // Advance to next player
-    game_state.current_player_turn = (game_state.current_player_turn + 1) % game_state.current_players;
+    msg!("Player {} revealed their shuffle seed", player.name);
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-// Call
-fn call(
-    _program_id: &Pubkey,
+// Add a layer of encryption to the deck and reshuffle it, a chunk at a time
+fn encrypt_deck(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
+    cards: Vec<sra::Point>,
+    key_commitment: [u8; 32],
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let player_account = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let player_token_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if game_state.game_stage == GameStage::Waiting || game_state.deck_encrypted() {
+        msg!("The deck is not waiting to be encrypted");
+        return Err(PokerError::NoCardsPending.into());
     }
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    let player_index = game_state.seat_of(player_account.key)?;
+
+    let hole_index = game_state.player(player_index)
+        .hole_index
+        .ok_or(PokerError::NotDealtIn)?;
+
+    if hole_index != game_state.encryption_passes {
+        msg!(
+            "Waiting on dealing position {} to encrypt the deck",
+            game_state.encryption_passes
+        );
+        return Err(PokerError::NotYourTurn.into());
+    }
 
-    let player_index = game_state
-        .players
-        .iter()
-        .position(|p| p.pubkey == *player_account.key)
-        .ok_or(ProgramError::Custom(3))?;
+    let posted = game_state.encrypted_cards as usize;
+    if posted > 0 && game_state.player(player_index).key_commitment != Some(key_commitment) {
+        msg!("Every part of an encryption pass must carry the same key commitment");
+        return Err(PokerError::InvalidEncryptedCards.into());
+    }
 
-    let call_amount = game_state.current_bet - game_state.players[player_index].current_bet;
+    // The program cannot see through the encryption, but it can insist on a
+    // full deck of distinct, well-formed cards
+    let deck_size = game_state.deck.len();
+    let end = posted + cards.len();
+    if cards.is_empty() || end > deck_size || !cards.iter().all(|&card| sra::is_element(card)) {
+        msg!("Expected up to {} more encrypted cards", deck_size - posted);
+        return Err(PokerError::InvalidEncryptedCards.into());
+    }
 
-    if call_amount > game_state.players[player_index].chips {
-        msg!("Insufficient chips to call");
-        return Err(ProgramError::InsufficientFunds);
+    game_state.deck[posted..end].copy_from_slice(&cards);
+    let mut sorted = game_state.deck[..end].to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+        msg!("Encrypted deck must hold {} distinct cards", deck_size);
+        return Err(PokerError::InvalidEncryptedCards.into());
     }
 
-    if call_amount > 0 {
-        // Deduct from player chips
-        game_state.players[player_index].chips -= call_amount;
-        game_state.players[player_index].current_bet += call_amount;
-        game_state.pot_total += call_amount;
+    game_state.player_mut(player_index).key_commitment = Some(key_commitment);
+    game_state.restart_action_clock()?;
 
+    if end < deck_size {
+        game_state.encrypted_cards = end as u8;
         msg!(
-            "Player {} called with {} USDC",
-            game_state.players[player_index].name,
-            call_amount as f64 / 1_000_000.0
+            "Player {} encrypted {} of {} cards",
+            game_state.player(player_index).name,
+            end,
+            deck_size
         );
+        game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+        return Ok(());
     }
 
-    // Advance to next player
-    game_state.current_player_turn = (game_state.current_player_turn + 1) % game_state.current_players;
+    game_state.encrypted_cards = 0;
+    game_state.encryption_passes += 1;
+
+    msg!(
+        "Player {} encrypted and shuffled the deck",
+        game_state.player(player_index).name
+    );
+
+    // Once every layer is on, only the hole cards and the board are dealt
+    if game_state.deck_encrypted() {
+        let dealt_cards = 2 * game_state.dealt_in() as usize + BOARD_CARDS;
+        game_state.deck.truncate(dealt_cards);
+        game_state.encrypted_deck = game_state.deck.clone();
+        msg!("Deck encrypted by every player; dealing hole cards");
+    }
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-// Raise
-fn raise(
-    _program_id: &Pubkey,
+// Strip a player's layer from the next deal step's cards
+fn unmask_cards(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    raise_amount: u64,
+    cards: Vec<sra::Point>,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let player_account = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let player_token_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
 
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    let player_index = game_state.seat_of(player_account.key)?;
 
-    let player_index = game_state
-        .players
-        .iter()
-        .position(|p| p.pubkey == *player_account.key)
-        .ok_or(ProgramError::Custom(3))?;
+    let hole_index = game_state.player(player_index)
+        .hole_index
+        .ok_or(PokerError::NotDealtIn)?;
 
-    let call_amount = game_state.current_bet - game_state.players[player_index].current_bet;
-    let total_amount = call_amount + raise_amount;
+    let step = game_state.player(player_index).unmask_step + 1;
+    if !game_state.deck_encrypted() || step > game_state.street_step() {
+        msg!("No cards are waiting to be unmasked");
+        return Err(PokerError::NoCardsPending.into());
+    }
 
-    if total_amount > game_state.players[player_index].chips {
-        msg!("Insufficient chips to raise");
-        return Err(ProgramError::InsufficientFunds);
+    // Players unmask in dealing order so nobody works from a stale card
+    let behind = game_state
+        .seated()
+        .any(|p| p.hole_index.is_some_and(|index| index < hole_index) && p.unmask_step < step);
+    if behind {
+        msg!("Waiting on earlier dealing positions to unmask");
+        return Err(PokerError::NotYourTurn.into());
     }
 
-    // Minimum raise is the big blind
-    if raise_amount < game_state.big_blind {
-        msg!("Raise amount must be at least the big blind");
-        return Err(ProgramError::InvalidArgument);
+    let positions = game_state.unmask_positions(step, hole_index);
+    if cards.len() != positions.len() || !cards.iter().all(|&card| sra::is_element(card)) {
+        msg!("Expected {} unmasked cards", positions.len());
+        return Err(PokerError::InvalidEncryptedCards.into());
     }
 
-    // Deduct from player chips
-    game_state.players[player_index].chips -= total_amount;
-    game_state.players[player_index].current_bet += total_amount;
-    game_state.current_bet += raise_amount;
-    game_state.pot_total += total_amount;
+    for (&position, &card) in positions.iter().zip(&cards) {
+        game_state.deck[position] = card;
+    }
+    let player = game_state.player_mut(player_index);
+    player.unmask_step = step;
+    if step > HOLE_CARDS_STEP {
+        player.board_unmasks.extend(&cards);
+    }
+    game_state.restart_action_clock()?;
 
     msg!(
-        "Player {} raised by {} USDC (total bet: {} USDC)",
-        game_state.players[player_index].name,
-        raise_amount as f64 / 1_000_000.0,
-        game_state.current_bet as f64 / 1_000_000.0
+        "Player {} unmasked {} cards",
+        game_state.player(player_index).name,
+        cards.len()
     );
 
-    // Advance to next player
-    game_state.current_player_turn = (game_state.current_player_turn + 1) % game_state.current_players;
+    if hole_index + 1 == game_state.dealt_in() {
+        game_state.cards_revealed = step;
+
+        if step > HOLE_CARDS_STEP {
+            let board: Option<Vec<u8>> = positions
+                .iter()
+                .map(|&position| sra::decode(game_state.deck[position]))
+                .collect();
+            match board {
+                Some(cards) => {
+                    game_state.board.extend(cards);
+                    msg!("Board: {:?}", game_state.board);
+                }
+                None => game_state.dispute_board(),
+            }
+        }
+    }
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-// End round and distribute pot
-fn end_round(
+// Reveal a key pair for showdown
+fn reveal_key(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    winner_index: u8,
+    encryption_key: sra::Key,
+    decryption_key: sra::Key,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let authority = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let winner_token_account = next_account_info(accounts_iter)?;
-    let pot_pda = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
 
-    if !authority.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if game_state.game_stage != GameStage::Showdown {
+        msg!("Keys are revealed at showdown");
+        return Err(PokerError::HandNotComplete.into());
     }
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    let player_index = game_state.seat_of(player_account.key)?;
 
-    if game_state.authority != *authority.key {
-        return Err(ProgramError::Custom(4));
+    let player = game_state.player_mut(player_index);
+    if player.hole_index.is_none() {
+        return Err(PokerError::NotDealtIn.into());
     }
 
-    let winner = &mut game_state.players[winner_index as usize];
+    let commitment = sra::key_commitment(player_account.key, encryption_key, decryption_key);
+    if player.key_commitment != Some(commitment) || !sra::is_key_pair(encryption_key, decryption_key) {
+        msg!("Key does not match commitment");
+        return Err(PokerError::InvalidKeyReveal.into());
+    }
 
-    // Get pot balance
-    let pot_account_data = TokenAccount::unpack(&pot_token_account.data.borrow())?;
-    let pot_balance = pot_account_data.amount;
+    player.decryption_key = Some(decryption_key);
 
-    if pot_balance > 0 {
-        // Transfer pot to winner using PDA signature
-        let game_state_key = game_state_account.key;
-        let seeds = &[
-            POT_SEED,
-            game_state_key.as_ref(),
-            &[game_state.pot_bump],
-        ];
+    msg!("Player {} revealed their key", player.name);
 
-        let transfer_ix = spl_token::instruction::transfer(
-            token_program.key,
-            pot_token_account.key,
-            winner_token_account.key,
-            pot_pda.key,
-            &[],
-            pot_balance,
-        )?;
+    game_state.restart_action_clock()?;
 
-        invoke_signed(
-            &transfer_ix,
-            &[
-                pot_token_account.clone(),
-                winner_token_account.clone(),
-                pot_pda.clone(),
-                token_program.clone(),
-            ],
-            &[seeds],
-        )?;
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
-        // Credit winner's chips
-        winner.chips += pot_balance;
+    Ok(())
+}
 
-        msg!(
-            "Player {} won {} USDC. New balance: {} USDC",
-            winner.name,
-            pot_balance as f64 / 1_000_000.0,
-            winner.chips as f64 / 1_000_000.0
-        );
+// Choose where the table's deck seeds come from
+fn set_randomness_oracle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    oracle: Option<Pubkey>,
+) -> ProgramResult {
+    let accounts = AuthorityAccounts::load(program_id, &mut accounts.iter())?;
+    let game_state_account = accounts.game_state;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
+
+    if game_state.game_stage != GameStage::Waiting {
+        msg!("Randomness can only be changed between hands");
+        return Err(PokerError::HandInProgress.into());
     }
 
-    // Reset game state for next round
-    for player in &mut game_state.players {
-        player.current_bet = 0;
-        player.has_folded = false;
-        player.is_active = true;
+    // Seeds committed for the next hand no longer apply
+    game_state.reset_shuffle();
+    game_state.randomness_oracle = oracle;
+
+    match oracle {
+        Some(oracle) => msg!("Decks are now seeded by oracle {}", oracle),
+        None => msg!("Decks are now seeded by player commit-reveal"),
     }
-    game_state.current_bet = 0;
-    game_state.pot_total = 0;
-    game_state.game_stage = GameStage::Waiting;
-    game_state.dealer_index = (game_state.dealer_index + 1) % game_state.current_players;
 
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-// Cash out chips to USDC
-fn cash_out(
+// Act for a player who has let the action deadline pass
+fn timeout_action(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    sit_out: bool,
 ) -> ProgramResult {
-    let accounts_iter = &mut accounts.iter();
-    let player_account = next_account_info(accounts_iter)?;
-    let game_state_account = next_account_info(accounts_iter)?;
-    let pot_token_account = next_account_info(accounts_iter)?;
-    let player_token_account = next_account_info(accounts_iter)?;
-    let pot_pda = next_account_info(accounts_iter)?;
-    let token_program = next_account_info(accounts_iter)?;
+    // Anyone can crank the table; they only need to sign
+    let PlayerAccounts {
+        game_state: game_state_account,
+        ..
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let stalled = game_state.stalled_seats();
+    let now = Clock::get()?.unix_timestamp;
+    if stalled.is_empty() || now <= game_state.action_deadline {
+        msg!("Action deadline is {}, now {}", game_state.action_deadline, now);
+        return Err(PokerError::ActionNotTimedOut.into());
+    }
+
+    if sit_out {
+        for &seat in &stalled {
+            let player = game_state.player_mut(seat);
+            if player.status == SeatStatus::Playing {
+                player.status = SeatStatus::SittingOut;
+                msg!("Player {} is sat out", player.name);
+            }
+        }
+    }
+
+    if game_state.is_betting_round() && game_state.cards_revealed >= game_state.street_step() {
+        let seat = stalled[0];
+        let current_bet = game_state.current_bet;
+        let player = game_state.player_mut(seat);
+        if player.current_bet < current_bet {
+            player.has_folded = true;
+            msg!("Player {} timed out and folded", player.name);
+        } else {
+            player.has_acted = true;
+            msg!("Player {} timed out and checked", player.name);
+        }
+
+        game_state.end_action();
+        game_state.restart_action_clock()?;
+    } else {
+        // The hand is replayed with the same button
+        game_state.forfeit_hand(&stalled)?;
+        game_state.reset_hand();
+    }
 
-    if !player_account.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// End round and pay each pot out to its winners' stacks
+fn end_round(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts = EndRoundAccounts::load(program_id, &mut accounts.iter())?;
+    let EndRoundAccounts {
+        game_state: game_state_account,
+        pot_token_account,
+        ..
+    } = accounts;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
+
+    if game_state.game_stage != GameStage::Showdown {
+        msg!("Hand has not reached showdown");
+        return Err(PokerError::HandNotComplete.into());
+    }
+
+    // A lone player left wins every pot without showing their cards
+    let players_in_hand = game_state.seated().filter(|p| !p.has_folded).count();
+    let pot_winners = if game_state.board_disputed {
+        game_state.check_keys_revealed()?;
+        None
+    } else if players_in_hand > 1 {
+        game_state.showdown_winners()?
+    } else {
+        Some(
+            game_state
+                .pots
+                .iter()
+                .map(|pot| pot.eligible_seats.clone())
+                .collect(),
+        )
+    };
+
+    match pot_winners {
+        Some(pot_winners) => {
+            // Only the chips committed this hand change hands; tokens stay in
+            // the vault until a player cashes out
+            let rake = game_state.take_rake()?;
+            if rake > 0 {
+                msg!("Rake: {}", game_state.ui_amount(rake));
+            }
+            let payouts = game_state.pot_payouts(&pot_winners)?;
+            let decimals = game_state.config.decimals;
+            for payout in &payouts {
+                let player = game_state.player_mut(payout.seat as usize);
+                player.chips = player
+                    .chips
+                    .checked_add(payout.amount)
+                    .ok_or(PokerError::Overflow)?;
+
+                msg!(
+                    "Player {} (seat {}) won {}. New balance: {}",
+                    player.name,
+                    payout.seat,
+                    token::ui_amount(payout.amount, decimals),
+                    token::ui_amount(player.chips, decimals)
+                );
+            }
+
+            let event = SettlementEvent { payouts, rake };
+            sol_log_data(&[&event.try_to_vec()?]);
+
+            game_state.move_button();
+        }
+        // The hand is replayed with the same button
+        None => game_state.call_off_undecodable()?,
     }
 
-    let mut game_state = GameState::try_from_slice(&game_state_account.data.borrow())?;
+    game_state.reset_hand();
+
+    check_vault_balance(&game_state, pot_token_account)?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Cash out chips to tokens
+fn cash_out(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let accounts = CashOutAccounts::load(program_id, &mut accounts.iter())?;
+    let CashOutAccounts {
+        player: player_account,
+        game_state: game_state_account,
+        pot_token_account,
+        player_token_account,
+        pot_authority: pot_pda,
+        mint,
+        token_program,
+    } = accounts;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
 
     // Can only cash out during waiting stage
     if game_state.game_stage != GameStage::Waiting {
         msg!("Can only cash out when not in a hand");
-        return Err(ProgramError::Custom(9));
+        return Err(PokerError::HandInProgress.into());
     }
 
-    let player_index = game_state
-        .players
-        .iter()
-        .position(|p| p.pubkey == *player_account.key)
-        .ok_or(ProgramError::Custom(3))?;
+    let player_index = game_state.seat_of(player_account.key)?;
 
-    let cash_out_amount = game_state.players[player_index].chips;
+    let cash_out_amount = game_state.player(player_index).chips;
 
     if cash_out_amount == 0 {
         msg!("No chips to cash out");
-        return Err(ProgramError::InsufficientFunds);
+        return Err(PokerError::NothingToCashOut.into());
     }
 
-    // Transfer USDC from pot to player using PDA signature
+    // Pay out from the vault, signing for the pot authority
     let game_state_key = game_state_account.key;
     let seeds = &[
         POT_SEED,
@@ -687,36 +3099,152 @@ fn cash_out(
         &[game_state.pot_bump],
     ];
 
-    let transfer_ix = spl_token::instruction::transfer(
-        token_program.key,
-        pot_token_account.key,
-        player_token_account.key,
-        pot_pda.key,
-        &[],
-        cash_out_amount,
-    )?;
-
-    invoke_signed(
-        &transfer_ix,
-        &[
-            pot_token_account.clone(),
-            player_token_account.clone(),
-            pot_pda.clone(),
-            token_program.clone(),
-        ],
-        &[seeds],
-    )?;
+    let transfer = VaultTransfer {
+        vault: pot_token_account,
+        funds: player_token_account,
+        mint,
+        program: token_program,
+        decimals: game_state.config.decimals,
+    };
+    game_state
+        .custody
+        .withdraw(&transfer, pot_pda, seeds, cash_out_amount)?;
 
     msg!(
-        "Player {} cashed out {} USDC",
-        game_state.players[player_index].name,
-        cash_out_amount as f64 / 1_000_000.0
+        "Player {} cashed out {}",
+        game_state.player(player_index).name,
+        game_state.ui_amount(cash_out_amount)
     );
 
-    // Remove player from game
-    game_state.players.remove(player_index);
+    // Free the seat; everyone else keeps theirs
+    game_state.players[player_index] = None;
     game_state.current_players -= 1;
 
+    check_vault_balance(&game_state, pot_token_account)?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Withdraw accrued rake to the house's treasury
+fn withdraw_rake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let accounts = WithdrawRakeAccounts::load(program_id, &mut accounts.iter())?;
+    let WithdrawRakeAccounts {
+        game_state: game_state_account,
+        pot_token_account,
+        treasury,
+        pot_authority: pot_pda,
+        mint,
+        token_program,
+        ..
+    } = accounts;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+    accounts.validate(&game_state)?;
+
+    let rake = game_state.rake_accrued;
+    if rake == 0 {
+        msg!("No rake to withdraw");
+        return Err(PokerError::NoRakeAccrued.into());
+    }
+
+    let game_state_key = game_state_account.key;
+    let seeds = &[POT_SEED, game_state_key.as_ref(), &[game_state.pot_bump]];
+    let transfer = VaultTransfer {
+        vault: pot_token_account,
+        funds: treasury,
+        mint,
+        program: token_program,
+        decimals: game_state.config.decimals,
+    };
+    game_state.custody.withdraw(&transfer, pot_pda, seeds, rake)?;
+    game_state.rake_accrued = 0;
+
+    msg!("Withdrew {} of rake", game_state.ui_amount(rake));
+
+    check_vault_balance(&game_state, pot_token_account)?;
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Sit out from the next hand
+fn sit_out(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.seat_of(player_account.key)?;
+    let player = game_state.player_mut(player_index);
+    if player.status != SeatStatus::Playing {
+        msg!("Player {} is {:?}, not playing", player.name, player.status);
+        return Err(PokerError::InvalidSeatStatus.into());
+    }
+
+    player.status = SeatStatus::SittingOut;
+    msg!("Player {} is sitting out", player.name);
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Sit back in from the next hand
+fn sit_in(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.seat_of(player_account.key)?;
+    let player = game_state.player_mut(player_index);
+    if !matches!(player.status, SeatStatus::SittingOut | SeatStatus::PendingLeave) {
+        // Busted players sit back in by buying in
+        msg!("Player {} is {:?}, not sitting out", player.name, player.status);
+        return Err(PokerError::InvalidSeatStatus.into());
+    }
+
+    player.status = SeatStatus::Playing;
+    msg!("Player {} is sitting in", player.name);
+
+    game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+// Leave the table once the current hand is over
+fn leave_after_hand(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let PlayerAccounts {
+        player: player_account,
+        game_state: game_state_account,
+    } = PlayerAccounts::load(program_id, &mut accounts.iter())?;
+
+    let mut game_state = GameState::deserialize(&mut &game_state_account.data.borrow()[..])?;
+
+    let player_index = game_state.seat_of(player_account.key)?;
+    let player = game_state.player_mut(player_index);
+    if player.status == SeatStatus::PendingLeave {
+        msg!("Player {} is already leaving", player.name);
+        return Err(PokerError::InvalidSeatStatus.into());
+    }
+
+    if player.chips == 0 && player.total_contribution == 0 && player.hole_index.is_none() {
+        // Nothing to cash out and no hand to finish
+        msg!("Player {} left seat {}", player.name, player_index);
+        game_state.players[player_index] = None;
+        game_state.current_players -= 1;
+    } else {
+        player.status = SeatStatus::PendingLeave;
+        msg!("Player {} will leave after this hand", player.name);
+    }
+
     game_state.serialize(&mut &mut game_state_account.data.borrow_mut()[..])?;
 
     Ok(())
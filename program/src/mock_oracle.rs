@@ -0,0 +1,64 @@
+//! Stand-in VRF oracle for running oracle-seeded tables offline under
+//! `solana-program-test`. Register `process_instruction` with
+//! `processor!`, create a `VrfResult::LEN` account owned by the mock, and
+//! publish randomness into it with `fulfill`. There is no proof: whoever
+//! calls `fulfill` picks the randomness, so this is only built off-chain.
+
+use crate::randomness::VrfResult;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum MockOracleInstruction {
+    /// Publish `randomness` as fulfilled at the current slot
+    /// Accounts expected:
+    /// 0. `[writable]` Randomness account owned by the mock oracle
+    Fulfill { randomness: [u8; 32] },
+}
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let MockOracleInstruction::Fulfill { randomness } =
+        MockOracleInstruction::try_from_slice(instruction_data)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let accounts_iter = &mut accounts.iter();
+    let result_account = next_account_info(accounts_iter)?;
+
+    if result_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let result = VrfResult {
+        randomness,
+        fulfilled_slot: Clock::get()?.slot,
+    };
+    result.serialize(&mut &mut result_account.data.borrow_mut()[..])?;
+
+    msg!("Mock oracle fulfilled randomness at slot {}", result.fulfilled_slot);
+
+    Ok(())
+}
+
+/// Instruction that publishes `randomness` into `result_account`
+pub fn fulfill(program_id: &Pubkey, result_account: &Pubkey, randomness: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![AccountMeta::new(*result_account, false)],
+        data: MockOracleInstruction::Fulfill { randomness }
+            .try_to_vec()
+            .unwrap(),
+    }
+}
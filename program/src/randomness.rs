@@ -0,0 +1,110 @@
+//! Where a hand's deck seed comes from.
+//!
+//! Tables shuffle from the players' commit-reveal seeds by default. A table
+//! can instead name a VRF oracle program with `SetRandomnessOracle`, in which
+//! case `StartRound` reads the seed from a fulfilled randomness account owned
+//! by that program. Either way the seed only fixes the order the deck enters
+//! the encryption passes in, so a source cannot choose anyone's cards.
+
+use crate::{error::PokerError, shuffle, Player};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+/// Oldest randomness `StartRound` accepts, in slots (about a minute)
+pub const MAX_RANDOMNESS_AGE_SLOTS: u64 = 150;
+
+/// Anything that can seed a hand's deck
+pub trait RandomnessSource {
+    fn deck_seed(&self) -> Result<[u8; 32], ProgramError>;
+}
+
+/// Seeds revealed by the players dealt into the hand, in seat order
+pub struct PlayerSeeds<'a> {
+    players: &'a [Option<Player>],
+}
+
+impl<'a> PlayerSeeds<'a> {
+    pub fn new(players: &'a [Option<Player>]) -> Self {
+        Self { players }
+    }
+}
+
+impl RandomnessSource for PlayerSeeds<'_> {
+    fn deck_seed(&self) -> Result<[u8; 32], ProgramError> {
+        Ok(shuffle::combine_seeds(
+            self.players
+                .iter()
+                .flatten()
+                .filter(|p| !p.has_folded)
+                .filter_map(|p| p.revealed_seed.as_ref()),
+        ))
+    }
+}
+
+/// Layout of a fulfilled randomness account. Oracles write it at the start
+/// of an account they own; `fulfilled_slot` stays 0 until the VRF proof has
+/// been verified and the randomness published.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct VrfResult {
+    pub randomness: [u8; 32],
+    pub fulfilled_slot: u64,
+}
+
+impl VrfResult {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// A VRF result that has passed the owner and freshness checks
+pub struct OracleRandomness {
+    result: VrfResult,
+}
+
+impl OracleRandomness {
+    /// Read `account` as randomness from `oracle`, fulfilled after
+    /// `last_used_slot` and no more than `MAX_RANDOMNESS_AGE_SLOTS` before
+    /// `current_slot`
+    pub fn load(
+        account: &AccountInfo,
+        oracle: &Pubkey,
+        last_used_slot: u64,
+        current_slot: u64,
+    ) -> Result<Self, ProgramError> {
+        if account.owner != oracle {
+            msg!("Randomness account is owned by {}, not {}", account.owner, oracle);
+            return Err(PokerError::InvalidRandomnessAccount.into());
+        }
+
+        let result = VrfResult::deserialize(&mut &account.data.borrow()[..])
+            .map_err(|_| PokerError::InvalidRandomnessAccount)?;
+
+        if result.fulfilled_slot == 0 {
+            msg!("Randomness has not been fulfilled yet");
+            return Err(PokerError::RandomnessNotFulfilled.into());
+        }
+
+        // Each result seeds one hand, and only while it is fresh
+        if result.fulfilled_slot <= last_used_slot
+            || current_slot.saturating_sub(result.fulfilled_slot) > MAX_RANDOMNESS_AGE_SLOTS
+        {
+            msg!(
+                "Randomness from slot {} is stale (last used {}, now {})",
+                result.fulfilled_slot,
+                last_used_slot,
+                current_slot
+            );
+            return Err(PokerError::StaleRandomness.into());
+        }
+
+        Ok(Self { result })
+    }
+
+    pub fn fulfilled_slot(&self) -> u64 {
+        self.result.fulfilled_slot
+    }
+}
+
+impl RandomnessSource for OracleRandomness {
+    fn deck_seed(&self) -> Result<[u8; 32], ProgramError> {
+        Ok(self.result.randomness)
+    }
+}
@@ -0,0 +1,43 @@
+//! Commit-reveal deck shuffling.
+//!
+//! Before each hand every seated player commits to a secret 32-byte seed by
+//! submitting `commitment(player, seed)`. Once the commitments are locked in
+//! the players reveal their seeds, and the deck is a Fisher-Yates shuffle
+//! driven by the hash of every revealed seed. No single participant, the
+//! house included, can choose the order of the cards without every other
+//! player's cooperation.
+
+use crate::hand_eval::{Card, DECK_SIZE};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+const COMMITMENT_DOMAIN: &[u8] = b"poker_seed_commitment";
+const DECK_DOMAIN: &[u8] = b"poker_deck";
+
+/// Hash a player commits to. Binding the player's key stops anyone from
+/// replaying another seat's commitment and later copying their reveal.
+pub fn commitment(player: &Pubkey, seed: &[u8; 32]) -> [u8; 32] {
+    hashv(&[COMMITMENT_DOMAIN, player.as_ref(), seed]).to_bytes()
+}
+
+/// Fold the revealed seeds, in seat order, into the seed for the deck
+pub fn combine_seeds<'a>(seeds: impl IntoIterator<Item = &'a [u8; 32]>) -> [u8; 32] {
+    seeds.into_iter().fold([0u8; 32], |combined, seed| {
+        hashv(&[DECK_DOMAIN, &combined, seed]).to_bytes()
+    })
+}
+
+/// Deterministic permutation of the 52 cards for a deck seed
+pub fn deck_from_seed(seed: &[u8; 32]) -> Vec<Card> {
+    let mut deck: Vec<Card> = (0..DECK_SIZE).collect();
+
+    for i in (1..deck.len()).rev() {
+        let draw = hashv(&[DECK_DOMAIN, seed, &(i as u32).to_le_bytes()]).to_bytes();
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&draw[..8]);
+        // Modulo bias over a 64-bit draw is below 2^-58 for a 52-card deck
+        let j = (u64::from_le_bytes(word) % (i as u64 + 1)) as usize;
+        deck.swap(i, j);
+    }
+
+    deck
+}
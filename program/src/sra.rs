@@ -0,0 +1,515 @@
+//! SRA commutative encryption for dealing cards nobody else can see.
+//!
+//! Each card is a point of the ristretto255 group and is encrypted by
+//! multiplying it by a player's secret scalar. Because `b(aP) = a(bP)`,
+//! every player can encrypt and secretly reshuffle the deck in turn, and
+//! later strip their own layer from any card in any order. A card is only
+//! readable once every other player has removed their layer from it, so a
+//! player's hole cards stay private while the board is unmasked by everyone
+//! at each street.
+//!
+//! A key is a pair `(e, d)` with `e * d = 1` modulo the group order. Players
+//! commit to their pair when they encrypt the deck and reveal it at
+//! showdown, where the program decrypts the dealt cards itself before running
+//! the evaluator. Keys are public from then on, so every hand needs a fresh
+//! pair.
+//!
+//! The card points are hashed onto the curve, so nobody knows the discrete
+//! log of one card in terms of another. Telling which card sits under a
+//! layer is then the decisional Diffie-Hellman problem in a group of order
+//! about 2^252. Point arithmetic goes through the runtime's curve25519
+//! syscalls; scalars are multiplied here in Montgomery form.
+
+use crate::hand_eval::{Card, DECK_SIZE};
+use solana_program::{hash::hashv, pubkey::Pubkey};
+use solana_zk_token_sdk::curve25519::{
+    ristretto::{multiply_ristretto, validate_ristretto, PodRistrettoPoint},
+    scalar::PodScalar,
+};
+
+/// Compressed ristretto point: a card, encrypted or not
+pub type Point = [u8; 32];
+
+/// Little-endian scalar below the group order
+pub type Key = [u8; 32];
+
+type Limbs = [u64; 4];
+
+/// Group order `2^252 + 27742317777372353535851937790883648493`
+const ORDER: Limbs = [0x5812_631a_5cf5_d3ed, 0x14de_f9de_a2f7_9cd6, 0, 0x1000_0000_0000_0000];
+
+/// `-ORDER^-1 mod 2^64`, for Montgomery reduction
+const ORDER_INV: u64 = 0xd2b5_1da3_1254_7e1b;
+
+/// `2^256 mod ORDER`, one in Montgomery form
+const R: Limbs = [
+    0xd6ec_3174_8d98_951d,
+    0xc6ef_5bf4_737d_cf70,
+    0xffff_ffff_ffff_fffe,
+    0x0fff_ffff_ffff_ffff,
+];
+
+/// `2^512 mod ORDER`, for moving into Montgomery form
+const R2: Limbs = [
+    0xa406_11e3_449c_0f01,
+    0xd00e_1ba7_6885_9347,
+    0xceec_73d2_17f5_be65,
+    0x0399_411b_7c30_9a3d,
+];
+
+const ONE: Limbs = [1, 0, 0, 0];
+
+const IDENTITY: Point = [0; 32];
+
+const KEY_DOMAIN: &[u8] = b"poker_sra_key";
+
+/// Ristretto point of each card, derived from
+/// `from_uniform_bytes(hashv([CARD_DOMAIN, [card], [0]]) || hashv([CARD_DOMAIN, [card], [1]]))`
+pub const CARD_DOMAIN: &[u8] = b"poker_sra_card";
+const CARD_POINTS: [Point; DECK_SIZE as usize] = [
+    [
+        0x08, 0x0b, 0xef, 0x1c, 0x6a, 0xe4, 0x72, 0x7b, 0xc2, 0x5d, 0x3e, 0x59, 0x01, 0xdd, 0x19,
+        0x74, 0x02, 0x13, 0xfa, 0x6c, 0x28, 0x92, 0x32, 0xf7, 0xe3, 0x60, 0xc9, 0xab, 0x0b, 0x5b,
+        0xcb, 0x14,
+    ],
+    [
+        0x1e, 0x8e, 0xd5, 0x44, 0xd9, 0xca, 0x70, 0x45, 0x50, 0xf5, 0x42, 0x17, 0x8f, 0x1d, 0x3c,
+        0x4f, 0xd6, 0xe3, 0x8a, 0x67, 0x5d, 0x72, 0xad, 0x6c, 0x47, 0xc8, 0x86, 0xd9, 0x0e, 0xb0,
+        0x3a, 0x30,
+    ],
+    [
+        0xaa, 0xd1, 0xa6, 0x61, 0x8f, 0x17, 0x09, 0x06, 0x05, 0x58, 0x3e, 0x75, 0x77, 0xbe, 0x61,
+        0x1a, 0x49, 0x9a, 0x7e, 0x65, 0x5c, 0x82, 0xd0, 0x54, 0x3c, 0xc8, 0x9a, 0xbe, 0x8c, 0xe0,
+        0x3d, 0x7a,
+    ],
+    [
+        0x14, 0xd0, 0x78, 0x4e, 0x0b, 0xb6, 0x95, 0xcd, 0xf6, 0xe1, 0x4c, 0xce, 0x2c, 0x84, 0x43,
+        0x57, 0x4c, 0x66, 0xde, 0xfa, 0x2c, 0x39, 0x36, 0x7b, 0x19, 0x9e, 0xa1, 0x0c, 0x40, 0xa4,
+        0x71, 0x22,
+    ],
+    [
+        0x06, 0x59, 0x46, 0xcc, 0xdb, 0xb4, 0x5e, 0x8b, 0x6f, 0x0a, 0xb5, 0x63, 0xde, 0x1e, 0x6f,
+        0x0c, 0x5d, 0x83, 0x2d, 0x39, 0x02, 0xbe, 0xe7, 0x39, 0x5e, 0x53, 0x6b, 0x6d, 0x3c, 0x31,
+        0xd5, 0x6b,
+    ],
+    [
+        0x6c, 0x49, 0xc5, 0x86, 0x51, 0x66, 0x77, 0x88, 0xcb, 0x07, 0xf5, 0x78, 0x92, 0xcc, 0x93,
+        0xfb, 0x9c, 0xff, 0x80, 0xd7, 0x12, 0xf4, 0x38, 0x01, 0xab, 0x61, 0xbb, 0xa2, 0x21, 0x0e,
+        0x0d, 0x65,
+    ],
+    [
+        0x48, 0x66, 0x58, 0x8d, 0x9f, 0xab, 0xda, 0xdb, 0x01, 0xa0, 0x65, 0x7b, 0x05, 0x25, 0x02,
+        0x28, 0xff, 0x22, 0xf0, 0x32, 0xa0, 0xb8, 0x60, 0xd3, 0x49, 0xe4, 0xb2, 0x30, 0x1a, 0x6c,
+        0xa5, 0x52,
+    ],
+    [
+        0x3c, 0x20, 0x13, 0x5d, 0x94, 0xff, 0x0a, 0x91, 0x69, 0x7a, 0x0d, 0x8b, 0x15, 0xcf, 0x5f,
+        0xaa, 0x97, 0x4d, 0x03, 0x06, 0x1b, 0x12, 0x2b, 0xe7, 0xcb, 0x17, 0x54, 0x97, 0x9b, 0x7a,
+        0xe1, 0x07,
+    ],
+    [
+        0x48, 0x33, 0xbd, 0xca, 0x7e, 0x0d, 0x12, 0xc5, 0x9d, 0x96, 0x47, 0xc6, 0x69, 0x20, 0xe1,
+        0x1f, 0xd0, 0x22, 0xca, 0x27, 0xd6, 0xed, 0x85, 0x6a, 0x70, 0x92, 0xac, 0x05, 0xdd, 0x30,
+        0xb1, 0x19,
+    ],
+    [
+        0xde, 0xa3, 0x91, 0xe2, 0xcf, 0xdd, 0x9a, 0xb1, 0x17, 0x5f, 0x7e, 0x50, 0xdb, 0x81, 0x5f,
+        0x31, 0x18, 0xd3, 0x32, 0xb5, 0x35, 0x8a, 0xc3, 0x49, 0x35, 0xb8, 0x9c, 0x6d, 0x9c, 0xec,
+        0x17, 0x26,
+    ],
+    [
+        0x88, 0xc6, 0xc3, 0xd1, 0x71, 0xa0, 0x56, 0xce, 0x06, 0xf7, 0xd6, 0x53, 0xdc, 0xa6, 0xd4,
+        0xb2, 0x25, 0xce, 0x3a, 0xcb, 0xa3, 0x79, 0x23, 0xc4, 0xe7, 0x1e, 0x40, 0xe5, 0xd4, 0x67,
+        0x42, 0x68,
+    ],
+    [
+        0x58, 0x6c, 0x71, 0x9e, 0xd5, 0xae, 0x08, 0x4e, 0x8f, 0x99, 0x7c, 0x38, 0x02, 0x75, 0xcb,
+        0x1b, 0xef, 0xba, 0xc8, 0x0a, 0x6d, 0xc0, 0x8d, 0x6b, 0x54, 0x7f, 0x34, 0xe2, 0xd0, 0xd2,
+        0x2e, 0x1f,
+    ],
+    [
+        0x24, 0x93, 0xbe, 0xd6, 0x77, 0xc8, 0xbb, 0x44, 0x11, 0xa0, 0xad, 0xea, 0xfb, 0x6d, 0x23,
+        0x9b, 0x92, 0x79, 0xe7, 0x40, 0xfb, 0x03, 0x16, 0x5e, 0x9b, 0xf4, 0x89, 0x6b, 0x1d, 0xa3,
+        0x12, 0x3b,
+    ],
+    [
+        0xdc, 0x96, 0xdf, 0xf3, 0x01, 0x2c, 0x5c, 0x00, 0x09, 0xb5, 0x76, 0xa8, 0x50, 0x77, 0xa3,
+        0x43, 0xb5, 0x29, 0xad, 0x55, 0x3a, 0xef, 0x31, 0xa3, 0x3a, 0xdc, 0xc9, 0x4a, 0x03, 0x12,
+        0x6c, 0x41,
+    ],
+    [
+        0x62, 0x76, 0xa4, 0xf5, 0xba, 0x8c, 0x07, 0x73, 0xa9, 0x35, 0x34, 0xc5, 0x54, 0x08, 0xb3,
+        0xb2, 0x14, 0x62, 0x11, 0xf4, 0xeb, 0xb4, 0x2e, 0x86, 0xd6, 0x55, 0x97, 0x69, 0xa6, 0x00,
+        0x5b, 0x3b,
+    ],
+    [
+        0x1a, 0x93, 0x2e, 0xd0, 0x43, 0xd4, 0x17, 0x2e, 0x9a, 0xef, 0x68, 0xd1, 0xb7, 0x41, 0x75,
+        0xb6, 0x28, 0x96, 0x6f, 0xed, 0x3f, 0x6f, 0xcb, 0x33, 0x39, 0xe9, 0x94, 0xe6, 0xe2, 0x7a,
+        0x09, 0x0a,
+    ],
+    [
+        0xcc, 0x38, 0xdb, 0x66, 0x9f, 0xcc, 0x1c, 0x2a, 0xac, 0xa6, 0xf1, 0xc5, 0x96, 0xf9, 0xeb,
+        0xa4, 0x9f, 0xef, 0x87, 0xe5, 0x9b, 0x0c, 0x32, 0x3d, 0xe7, 0x46, 0xf4, 0xb2, 0xcf, 0x55,
+        0x54, 0x31,
+    ],
+    [
+        0x7c, 0x86, 0x90, 0x44, 0xc9, 0xfa, 0x69, 0x70, 0x55, 0xc2, 0x53, 0x25, 0x2e, 0xb4, 0x95,
+        0x8a, 0x31, 0x81, 0x3c, 0x67, 0x58, 0xd7, 0x08, 0x5f, 0x00, 0xea, 0x8e, 0xe7, 0xee, 0x24,
+        0xf6, 0x61,
+    ],
+    [
+        0x74, 0xdd, 0x34, 0x9b, 0x0e, 0xda, 0xc3, 0x8b, 0xa2, 0xa3, 0x0c, 0xb9, 0x7c, 0x07, 0xb7,
+        0x1c, 0x1a, 0x57, 0x63, 0x08, 0xdf, 0x1b, 0xb4, 0x48, 0x69, 0x00, 0xba, 0xf9, 0xb1, 0x59,
+        0xaf, 0x3b,
+    ],
+    [
+        0x82, 0x85, 0xc8, 0x0b, 0x12, 0xed, 0x81, 0x52, 0x14, 0x48, 0x99, 0xff, 0x9e, 0xf8, 0x1c,
+        0xde, 0x2d, 0x2e, 0xb1, 0x00, 0xad, 0x17, 0xc9, 0x5d, 0x14, 0x93, 0x43, 0x0c, 0xd8, 0x7c,
+        0x15, 0x2f,
+    ],
+    [
+        0x86, 0xb7, 0x39, 0x75, 0x18, 0x36, 0xc5, 0x35, 0xa2, 0xd7, 0xd7, 0xf9, 0x64, 0x16, 0x06,
+        0x21, 0xff, 0x0c, 0xca, 0x59, 0xf8, 0x5c, 0x13, 0x7f, 0x77, 0x93, 0xaf, 0xca, 0x1a, 0x27,
+        0x62, 0x25,
+    ],
+    [
+        0x8a, 0x33, 0xe8, 0x98, 0x7a, 0x38, 0x8f, 0xf2, 0xf2, 0xf0, 0xe1, 0x70, 0x28, 0xdf, 0x89,
+        0x95, 0xf1, 0xd1, 0xf9, 0xfb, 0x95, 0xd6, 0x6b, 0x41, 0x78, 0x16, 0x15, 0x85, 0xf4, 0xbd,
+        0xa4, 0x24,
+    ],
+    [
+        0x5e, 0x4e, 0xd3, 0xa2, 0x12, 0xd0, 0xf8, 0xcb, 0xc7, 0x26, 0x6f, 0x25, 0xe6, 0x76, 0x0a,
+        0xdf, 0xb0, 0x5b, 0xd3, 0xaf, 0x13, 0x41, 0x79, 0x49, 0x55, 0x23, 0x35, 0x72, 0xa1, 0xec,
+        0x47, 0x18,
+    ],
+    [
+        0xe2, 0xff, 0xb3, 0x8e, 0x10, 0xc2, 0x57, 0xf3, 0x1f, 0xf3, 0x86, 0x69, 0x35, 0xfa, 0xe0,
+        0x69, 0x40, 0x14, 0xe7, 0x13, 0x8e, 0xd8, 0x3a, 0xfd, 0xdd, 0x4b, 0x14, 0x31, 0x36, 0x2b,
+        0x05, 0x1b,
+    ],
+    [
+        0x42, 0xd6, 0xf5, 0x47, 0x85, 0x98, 0xe5, 0xcb, 0x79, 0x6d, 0x9d, 0x11, 0x90, 0xea, 0x03,
+        0x1a, 0x4c, 0xfd, 0x3e, 0xa5, 0x40, 0x57, 0x59, 0x43, 0x51, 0x0c, 0x86, 0x7d, 0x73, 0xbd,
+        0x0c, 0x6e,
+    ],
+    [
+        0x7e, 0xe8, 0x91, 0xa3, 0x0a, 0xdf, 0xad, 0xc7, 0x4a, 0x9e, 0xe9, 0x41, 0x71, 0xcd, 0x60,
+        0x61, 0x93, 0x46, 0x94, 0xe3, 0x58, 0xcf, 0x19, 0x63, 0xb1, 0x95, 0xb4, 0xf9, 0x57, 0xe9,
+        0xdf, 0x27,
+    ],
+    [
+        0x6e, 0xce, 0xa1, 0xba, 0x79, 0x3c, 0x12, 0xa6, 0xd2, 0x4d, 0x14, 0xdc, 0xf9, 0x98, 0x9a,
+        0xef, 0xb7, 0x79, 0xd9, 0xfc, 0xcc, 0x4e, 0xaf, 0x65, 0xde, 0xcc, 0x66, 0x13, 0xf5, 0x8c,
+        0x7c, 0x45,
+    ],
+    [
+        0x40, 0x00, 0x2e, 0x7c, 0xcf, 0xb5, 0x60, 0xdf, 0x65, 0xf0, 0xa9, 0x3a, 0x61, 0x1b, 0xbc,
+        0xdc, 0x13, 0x6c, 0x83, 0xc9, 0x48, 0xd6, 0x34, 0xe0, 0x21, 0xd8, 0x08, 0xff, 0xcb, 0x8a,
+        0x70, 0x14,
+    ],
+    [
+        0x92, 0xbc, 0xc8, 0xe9, 0x78, 0x90, 0xa9, 0x63, 0x2e, 0xb6, 0x95, 0xc5, 0x47, 0x69, 0x17,
+        0xe8, 0x3e, 0xd1, 0x2a, 0x9c, 0x1a, 0xbd, 0x87, 0x5a, 0x16, 0x73, 0x9d, 0x54, 0x63, 0x97,
+        0xd3, 0x66,
+    ],
+    [
+        0xb0, 0x6d, 0x07, 0x6c, 0xf7, 0x44, 0x56, 0x9b, 0x89, 0x00, 0xcb, 0x08, 0x9c, 0x9b, 0xce,
+        0x52, 0x1e, 0x11, 0xbb, 0x82, 0x7f, 0x74, 0x7f, 0x4d, 0x89, 0x73, 0xef, 0x35, 0x03, 0xd5,
+        0x7b, 0x5d,
+    ],
+    [
+        0x50, 0x17, 0x94, 0x3a, 0x16, 0xe7, 0x06, 0x5c, 0xa8, 0x1d, 0xed, 0xfb, 0xd3, 0xa1, 0x2b,
+        0xcc, 0x12, 0xa7, 0xed, 0xa3, 0x7a, 0x6a, 0x21, 0x3c, 0xc9, 0xdf, 0x26, 0xe4, 0xc2, 0x44,
+        0xdc, 0x58,
+    ],
+    [
+        0xe0, 0x47, 0xe0, 0x9e, 0x19, 0xac, 0x2c, 0xc9, 0x21, 0x5f, 0xfd, 0x39, 0xd2, 0xf2, 0x60,
+        0x05, 0x2c, 0xce, 0xcc, 0xf7, 0x29, 0xec, 0x4e, 0x18, 0x3b, 0xd8, 0x5e, 0x25, 0xb5, 0x5a,
+        0x5e, 0x16,
+    ],
+    [
+        0x42, 0x7d, 0x9e, 0xf2, 0xa1, 0xa2, 0xf6, 0x89, 0xb1, 0x17, 0x56, 0x7e, 0xae, 0x39, 0x06,
+        0xee, 0x21, 0xf9, 0x37, 0xa1, 0x8a, 0xda, 0x15, 0x93, 0x2b, 0xbf, 0x74, 0x23, 0xe6, 0x4e,
+        0xc8, 0x34,
+    ],
+    [
+        0xc4, 0xd9, 0x05, 0x71, 0x31, 0x5e, 0x89, 0x06, 0xfc, 0xde, 0x9f, 0xf3, 0x3d, 0xad, 0xf0,
+        0xd7, 0x13, 0x64, 0xe7, 0xa2, 0xfb, 0x95, 0x46, 0x20, 0x31, 0x85, 0x8a, 0x6d, 0xbd, 0x2f,
+        0x5f, 0x79,
+    ],
+    [
+        0x24, 0x77, 0x0e, 0x77, 0x14, 0x9b, 0x29, 0x75, 0x90, 0xe1, 0x11, 0xbe, 0x54, 0x25, 0x96,
+        0x37, 0x99, 0x97, 0xf3, 0xd4, 0xc3, 0x88, 0xee, 0x65, 0xfd, 0x62, 0x22, 0x34, 0x4c, 0x82,
+        0x80, 0x1c,
+    ],
+    [
+        0xbe, 0xa7, 0x2e, 0x45, 0x33, 0x9a, 0x2d, 0x5f, 0x29, 0x39, 0x4f, 0xe1, 0xe7, 0x15, 0x3b,
+        0xfa, 0x5c, 0x55, 0x5e, 0x63, 0x53, 0x27, 0xc3, 0x99, 0xb7, 0xe0, 0x51, 0x84, 0x2a, 0x26,
+        0xe0, 0x7c,
+    ],
+    [
+        0xf6, 0x92, 0x01, 0xd6, 0xe7, 0x06, 0x9b, 0x22, 0x6a, 0x4c, 0x3d, 0xe3, 0x31, 0x47, 0x71,
+        0x0f, 0x8b, 0x67, 0x74, 0x39, 0x7f, 0xe4, 0x0a, 0xf8, 0x58, 0x4c, 0x94, 0x66, 0x5d, 0x67,
+        0xa8, 0x2f,
+    ],
+    [
+        0x92, 0x2b, 0xda, 0x43, 0xe8, 0xa6, 0xae, 0x1e, 0xa0, 0x5d, 0x65, 0xd9, 0x5e, 0xf5, 0xb9,
+        0xf1, 0x19, 0x87, 0x1e, 0x49, 0xa2, 0xca, 0xd6, 0x0d, 0xc7, 0xa3, 0xa8, 0x1c, 0x4c, 0x9c,
+        0x18, 0x54,
+    ],
+    [
+        0x1a, 0x82, 0x5a, 0x20, 0xb9, 0xd3, 0xc9, 0xaa, 0x88, 0xd4, 0x6a, 0xa8, 0xf1, 0x30, 0xd2,
+        0xaa, 0xe4, 0x05, 0x04, 0x0b, 0xfc, 0x93, 0x90, 0xe2, 0x42, 0x5a, 0x0e, 0xa7, 0xc2, 0x27,
+        0x3f, 0x1a,
+    ],
+    [
+        0x62, 0xbd, 0x90, 0x2e, 0xe5, 0xa7, 0x11, 0xf6, 0x9e, 0x1d, 0x49, 0xbb, 0xab, 0x9d, 0x81,
+        0x57, 0x19, 0x45, 0x51, 0x88, 0xc5, 0x47, 0x07, 0x75, 0x96, 0xb7, 0x3d, 0x4a, 0x4d, 0xba,
+        0x97, 0x06,
+    ],
+    [
+        0xce, 0x0c, 0x64, 0x77, 0xde, 0xac, 0x4f, 0x08, 0x59, 0x88, 0x81, 0xb9, 0xea, 0x44, 0x0a,
+        0xc0, 0xf7, 0x00, 0x59, 0x03, 0xcb, 0xd5, 0x7b, 0xfe, 0x75, 0x53, 0xdb, 0x28, 0x07, 0x36,
+        0xbc, 0x49,
+    ],
+    [
+        0xcc, 0x60, 0xbe, 0x59, 0xf2, 0x25, 0xa7, 0x18, 0x1f, 0x47, 0x10, 0xa7, 0xcc, 0xb4, 0xf2,
+        0x92, 0xb8, 0x65, 0xd1, 0xb6, 0xb3, 0xb0, 0x4c, 0x9d, 0x9f, 0x8c, 0xa5, 0xe0, 0x68, 0xd8,
+        0x31, 0x48,
+    ],
+    [
+        0x48, 0x96, 0x87, 0x8b, 0x1f, 0xcf, 0xd5, 0xe1, 0x1a, 0xbb, 0x51, 0xda, 0x08, 0xc8, 0xf0,
+        0x85, 0xcc, 0xf1, 0xa6, 0xb3, 0x5c, 0x34, 0x29, 0x77, 0x21, 0x31, 0x67, 0x4f, 0x97, 0x73,
+        0xca, 0x1f,
+    ],
+    [
+        0xbc, 0x01, 0x05, 0xbb, 0xd2, 0x3f, 0x7c, 0xbc, 0x25, 0x99, 0x98, 0x1b, 0x40, 0xeb, 0xf7,
+        0xef, 0x35, 0x75, 0x4c, 0x6b, 0x4f, 0x21, 0xce, 0x12, 0x63, 0xb6, 0xb0, 0x5f, 0x9f, 0xf5,
+        0xe2, 0x3e,
+    ],
+    [
+        0xe4, 0x64, 0x40, 0xa1, 0xb0, 0xef, 0xd9, 0x89, 0xe5, 0xc1, 0x04, 0xeb, 0xff, 0x3c, 0x1d,
+        0x80, 0xd2, 0xaa, 0x10, 0xcb, 0x7d, 0x83, 0xd7, 0xa8, 0x3e, 0xaa, 0x29, 0x4b, 0x9a, 0x1d,
+        0x43, 0x11,
+    ],
+    [
+        0x7c, 0x93, 0x74, 0xf9, 0xc8, 0x55, 0x22, 0x52, 0x3b, 0xb9, 0x3a, 0x45, 0xa4, 0xba, 0x72,
+        0x8b, 0xdc, 0xa6, 0x21, 0x1a, 0x89, 0xe7, 0xa3, 0x38, 0x86, 0x26, 0x9d, 0x14, 0xf5, 0x88,
+        0x2d, 0x4f,
+    ],
+    [
+        0x24, 0x98, 0xe9, 0x6c, 0xbc, 0x72, 0xb6, 0x58, 0x25, 0x67, 0x54, 0xf8, 0xad, 0x95, 0xc5,
+        0x7d, 0x0e, 0x1a, 0x0d, 0xad, 0x8f, 0x86, 0x7d, 0x3b, 0x20, 0xd9, 0x8a, 0x95, 0x8c, 0x23,
+        0x5f, 0x21,
+    ],
+    [
+        0xc0, 0xf7, 0xcb, 0xa4, 0x52, 0xad, 0xb6, 0x79, 0xb3, 0xfc, 0x29, 0x71, 0x38, 0x9a, 0x0b,
+        0x1b, 0xd0, 0xe6, 0x7d, 0x6d, 0x88, 0x95, 0x84, 0x10, 0x25, 0x95, 0xf6, 0x99, 0x00, 0x5d,
+        0xf8, 0x00,
+    ],
+    [
+        0x06, 0x4e, 0x3d, 0x64, 0xe9, 0x57, 0xef, 0x0c, 0x31, 0x20, 0x7b, 0xd5, 0x22, 0x81, 0x90,
+        0x85, 0xe1, 0x1a, 0xf7, 0xb5, 0xc4, 0x55, 0xd0, 0x74, 0x56, 0x84, 0xb9, 0x62, 0x00, 0x58,
+        0x62, 0x2c,
+    ],
+    [
+        0x7a, 0x55, 0xfa, 0xc8, 0xff, 0x8a, 0x70, 0xd0, 0x3a, 0xdf, 0xb4, 0xaa, 0x4b, 0xed, 0x2a,
+        0xa9, 0x0f, 0xc1, 0xb1, 0xae, 0x56, 0xdc, 0x24, 0xcc, 0xef, 0xc6, 0xeb, 0xf6, 0x87, 0x25,
+        0xca, 0x14,
+    ],
+    [
+        0xae, 0x6d, 0x8f, 0xcd, 0x26, 0x71, 0x76, 0x51, 0xd6, 0x52, 0x04, 0x5a, 0xbf, 0x6a, 0x38,
+        0x46, 0xf0, 0x1c, 0x38, 0x43, 0x97, 0xa7, 0x27, 0x36, 0xac, 0x8e, 0xf4, 0x58, 0xc0, 0x17,
+        0xf6, 0x44,
+    ],
+    [
+        0x46, 0x49, 0x7c, 0xea, 0x31, 0x79, 0x8f, 0xa3, 0xc7, 0x88, 0x8c, 0x74, 0x05, 0x3c, 0x15,
+        0x4a, 0x06, 0x4b, 0x4c, 0x83, 0x7e, 0x58, 0x99, 0xeb, 0x9c, 0x81, 0x5d, 0x68, 0x4f, 0x41,
+        0x60, 0x4e,
+    ],
+];
+
+/// Point that stands for a card
+pub fn encode(card: Card) -> Point {
+    CARD_POINTS[card as usize]
+}
+
+/// Card behind a fully decrypted value, if it is one
+pub fn decode(value: Point) -> Option<Card> {
+    (0..DECK_SIZE).find(|&card| encode(card) == value)
+}
+
+/// Whether `value` can appear in an encrypted deck: a valid point other than
+/// the identity, which no key can move
+pub fn is_element(value: Point) -> bool {
+    value != IDENTITY && validate_ristretto(&PodRistrettoPoint(value))
+}
+
+/// Add a player's layer to a card
+pub fn encrypt(value: Point, encryption_key: Key) -> Point {
+    multiply(value, encryption_key)
+}
+
+/// Strip a player's layer from a card, or every layer at once with the
+/// product of the decryption keys from `combine_keys`
+pub fn decrypt(value: Point, decryption_key: Key) -> Point {
+    multiply(value, decryption_key)
+}
+
+/// Whether `encryption_key` and `decryption_key` undo each other
+pub fn is_key_pair(encryption_key: Key, decryption_key: Key) -> bool {
+    let (encryption_key, decryption_key) = (to_limbs(&encryption_key), to_limbs(&decryption_key));
+    is_key(&encryption_key)
+        && encryption_key != ONE
+        && is_key(&decryption_key)
+        && mul(&encryption_key, &decryption_key) == ONE
+}
+
+/// Decryption key for `encryption_key`, or `None` if it is not a key
+pub fn decryption_key(encryption_key: Key) -> Option<Key> {
+    let key = to_limbs(&encryption_key);
+    if !is_key(&key) || key == ONE {
+        return None;
+    }
+
+    // Fermat: key^(ORDER - 2) is the inverse, the order being prime
+    let mut exponent = ORDER;
+    exponent[0] -= 2;
+    let base = mont_mul(&key, &R2);
+    let mut inverse = R;
+    for bit in (0..256).rev() {
+        inverse = mont_mul(&inverse, &inverse);
+        if exponent[bit / 64] >> (bit % 64) & 1 == 1 {
+            inverse = mont_mul(&inverse, &base);
+        }
+    }
+
+    Some(to_bytes(&mont_mul(&inverse, &ONE)))
+}
+
+/// Encryption key from 64 secret random bytes, reduced modulo the group
+/// order without noticeable bias
+pub fn key_from_bytes(bytes: &[u8; 64]) -> Key {
+    let low = to_limbs(bytes[..32].try_into().unwrap());
+    let high = to_limbs(bytes[32..].try_into().unwrap());
+
+    // low + high * 2^256, with 2^256 = R (mod ORDER)
+    let low = mont_mul(&mont_mul(&low, &R2), &ONE);
+    let high = mont_mul(&high, &R2);
+    to_bytes(&add(&low, &high))
+}
+
+/// Single decryption key that strips every given layer at once
+pub fn combine_keys(decryption_keys: impl IntoIterator<Item = Key>) -> Key {
+    let combined = decryption_keys
+        .into_iter()
+        .fold(ONE, |combined, key| mul(&combined, &to_limbs(&key)));
+    to_bytes(&combined)
+}
+
+/// Hash a player commits to before their key touches the deck
+pub fn key_commitment(player: &Pubkey, encryption_key: Key, decryption_key: Key) -> [u8; 32] {
+    hashv(&[KEY_DOMAIN, player.as_ref(), &encryption_key, &decryption_key]).to_bytes()
+}
+
+/// `key * value`, or the identity if either is malformed
+fn multiply(value: Point, key: Key) -> Point {
+    multiply_ristretto(&PodScalar(key), &PodRistrettoPoint(value)).map_or(IDENTITY, |point| point.0)
+}
+
+/// Nonzero and below the group order
+fn is_key(key: &Limbs) -> bool {
+    *key != [0; 4] && less_than(key, &ORDER)
+}
+
+/// `a * b` modulo the group order, for `a` and `b` below it
+fn mul(a: &Limbs, b: &Limbs) -> Limbs {
+    mont_mul(&mont_mul(a, b), &R2)
+}
+
+/// `a + b` modulo the group order, for `a` and `b` below it
+fn add(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut sum = [0; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        let total = a[i] as u128 + b[i] as u128 + carry;
+        sum[i] = total as u64;
+        carry = total >> 64;
+    }
+
+    // The order is below 2^253, so the sum never carries out
+    if less_than(&sum, &ORDER) {
+        sum
+    } else {
+        sub(&sum, &ORDER)
+    }
+}
+
+/// Montgomery product `a * b / 2^256` modulo the group order, for
+/// `a * b < ORDER * 2^256`
+fn mont_mul(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut t = [0u64; 6];
+    for &b_limb in b {
+        let mut carry = 0u128;
+        for j in 0..4 {
+            let total = t[j] as u128 + a[j] as u128 * b_limb as u128 + carry;
+            t[j] = total as u64;
+            carry = total >> 64;
+        }
+        let total = t[4] as u128 + carry;
+        t[4] = total as u64;
+        t[5] = (total >> 64) as u64;
+
+        // Add the multiple of the order that clears the low limb, then drop it
+        let m = t[0].wrapping_mul(ORDER_INV);
+        let mut carry = (t[0] as u128 + m as u128 * ORDER[0] as u128) >> 64;
+        for j in 1..4 {
+            let total = t[j] as u128 + m as u128 * ORDER[j] as u128 + carry;
+            t[j - 1] = total as u64;
+            carry = total >> 64;
+        }
+        let total = t[4] as u128 + carry;
+        t[3] = total as u64;
+        t[4] = t[5] + (total >> 64) as u64;
+    }
+
+    let product = [t[0], t[1], t[2], t[3]];
+    if t[4] == 0 && less_than(&product, &ORDER) {
+        product
+    } else {
+        sub(&product, &ORDER)
+    }
+}
+
+/// `a - b` modulo 2^256
+fn sub(a: &Limbs, b: &Limbs) -> Limbs {
+    let mut difference = [0; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (partial, borrow_a) = a[i].overflowing_sub(b[i]);
+        let (partial, borrow_b) = partial.overflowing_sub(borrow as u64);
+        difference[i] = partial;
+        borrow = borrow_a || borrow_b;
+    }
+    difference
+}
+
+fn less_than(a: &Limbs, b: &Limbs) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+fn to_limbs(bytes: &[u8; 32]) -> Limbs {
+    let mut limbs = [0; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+fn to_bytes(limbs: &Limbs) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(8).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
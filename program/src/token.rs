@@ -0,0 +1,63 @@
+//! Token accounts and mints under either token program.
+//!
+//! A table plays in whatever mint it was created with, owned by SPL Token or
+//! Token-2022. Both programs share the base account and mint layouts, and
+//! Token-2022 appends extensions after them, so everything here unpacks
+//! through `StateWithExtensions` and works for either.
+
+use crate::error::PokerError;
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+/// Mint extensions that would move or lock tokens behind the table's back,
+/// so the vault could no longer back every chip
+const UNSUPPORTED_EXTENSIONS: [ExtensionType; 6] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::ConfidentialTransferFeeConfig,
+    ExtensionType::DefaultAccountState,
+    ExtensionType::NonTransferable,
+    ExtensionType::PermanentDelegate,
+    ExtensionType::TransferHook,
+];
+
+/// Whether `id` is SPL Token or Token-2022
+pub fn is_token_program(id: &Pubkey) -> bool {
+    *id == spl_token::id() || *id == spl_token_2022::id()
+}
+
+/// The base state of a token account owned by either token program
+pub fn unpack_account(account: &AccountInfo) -> Result<Account, ProgramError> {
+    if !is_token_program(account.owner) {
+        return Err(PokerError::InvalidTokenAccount.into());
+    }
+    Ok(StateWithExtensions::<Account>::unpack(&account.data.borrow())?.base)
+}
+
+/// Unpack a mint a table can play in, rejecting Token-2022 mints with
+/// extensions that break pot accounting
+pub fn unpack_mint(mint: &AccountInfo) -> Result<Mint, ProgramError> {
+    if !is_token_program(mint.owner) {
+        msg!("{} is not owned by a token program", mint.key);
+        return Err(PokerError::InvalidMint.into());
+    }
+
+    let data = mint.data.borrow();
+    let state =
+        StateWithExtensions::<Mint>::unpack(&data).map_err(|_| PokerError::InvalidMint)?;
+    for extension in state.get_extension_types()? {
+        if UNSUPPORTED_EXTENSIONS.contains(&extension) {
+            msg!("Mints with the {:?} extension are not supported", extension);
+            return Err(PokerError::UnsupportedMint.into());
+        }
+    }
+
+    Ok(state.base)
+}
+
+/// `amount` in whole tokens for logs, e.g. 1_250_000 at 6 decimals is "1.25"
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    spl_token_2022::amount_to_ui_amount_string_trimmed(amount, decimals)
+}
@@ -0,0 +1,259 @@
+mod common;
+
+use common::{
+    assert_instruction_error, assert_poker_error, buy_in_instruction, table_config, TestTable,
+    BUY_IN, TABLE_ID,
+};
+use solana_poker::{PokerError, PokerInstruction};
+use solana_program::{
+    instruction::{AccountMeta, InstructionError},
+    pubkey::Pubkey,
+};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn game_state_must_be_the_programs_and_writable() {
+    let mut table = TestTable::seated(2).await;
+    let player = table.players[0].keypair.pubkey();
+
+    // A byte-for-byte copy owned by another program is not the table
+    let spoofed = table.spoofed_game_state(Pubkey::new_unique()).await;
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::Check,
+                vec![AccountMeta::new(player, true), AccountMeta::new(spoofed, false)],
+                Some(0),
+            )
+            .await,
+        PokerError::InvalidGameAccount,
+    );
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[1] = AccountMeta::new(spoofed, false);
+    assert_poker_error(
+        table
+            .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+            .await,
+        PokerError::InvalidGameAccount,
+    );
+
+    let game_state = table.game_state;
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::Check,
+                vec![
+                    AccountMeta::new(player, true),
+                    AccountMeta::new_readonly(game_state, false),
+                ],
+                Some(0),
+            )
+            .await,
+        PokerError::AccountNotWritable,
+    );
+
+    // Naming a player without their signature does nothing
+    assert_instruction_error(
+        table
+            .send_instruction(
+                PokerInstruction::Check,
+                vec![AccountMeta::new(player, false), AccountMeta::new(game_state, false)],
+                None,
+            )
+            .await,
+        InstructionError::MissingRequiredSignature,
+    );
+}
+
+#[tokio::test]
+async fn buy_in_rejects_spoofed_token_accounts() {
+    let mut table = TestTable::new(1).await;
+    table.initialize(2).await.unwrap();
+    let player = table.players[0].keypair.pubkey();
+    let buy_in = buy_in_instruction(BUY_IN);
+
+    // Paying into the player's own account instead of the vault
+    let own_account = table.add_token_account(table.mint, player, 0);
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[3] = AccountMeta::new(own_account, false);
+    assert_poker_error(
+        table.send_instruction(buy_in, accounts, Some(0)).await,
+        PokerError::InvalidPotAccount,
+    );
+
+    // Paying with some other token
+    let other_mint = table.add_token_account(Pubkey::new_unique(), player, BUY_IN);
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[2] = AccountMeta::new(other_mint, false);
+    assert_poker_error(
+        table
+            .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+            .await,
+        PokerError::InvalidTokenAccount,
+    );
+
+    // Naming some other mint for the transfer
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[4] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    assert_poker_error(
+        table
+            .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+            .await,
+        PokerError::InvalidMint,
+    );
+
+    // A look-alike token program, or the token program the mint is not under
+    for token_program in [Pubkey::new_unique(), spl_token_2022::id()] {
+        let mut accounts = table.buy_in_accounts(0);
+        accounts[5] = AccountMeta::new_readonly(token_program, false);
+        assert_instruction_error(
+            table
+                .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+                .await,
+            InstructionError::IncorrectProgramId,
+        );
+    }
+
+    assert_eq!(table.game_state().await.current_players, 0);
+    table.buy_in(0, BUY_IN).await.unwrap();
+}
+
+#[tokio::test]
+async fn cash_out_rejects_spoofed_accounts() {
+    let mut table = TestTable::seated(2).await;
+    let player = table.players[0].keypair.pubkey();
+
+    let other_mint = table.add_token_account(Pubkey::new_unique(), player, 0);
+    let mut accounts = table.cash_out_accounts(0);
+    accounts[3] = AccountMeta::new(other_mint, false);
+    assert_poker_error(
+        table
+            .send_instruction(PokerInstruction::CashOut, accounts, Some(0))
+            .await,
+        PokerError::InvalidTokenAccount,
+    );
+
+    let mut accounts = table.cash_out_accounts(0);
+    accounts[4] = AccountMeta::new_readonly(player, false);
+    assert_poker_error(
+        table
+            .send_instruction(PokerInstruction::CashOut, accounts, Some(0))
+            .await,
+        PokerError::InvalidPotAccount,
+    );
+
+    let other_pot = table.add_token_account(table.mint, player, BUY_IN);
+    let mut accounts = table.cash_out_accounts(0);
+    accounts[2] = AccountMeta::new(other_pot, false);
+    assert_poker_error(
+        table
+            .send_instruction(PokerInstruction::CashOut, accounts, Some(0))
+            .await,
+        PokerError::InvalidPotAccount,
+    );
+
+    let mut accounts = table.cash_out_accounts(0);
+    accounts[6] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    assert_instruction_error(
+        table
+            .send_instruction(PokerInstruction::CashOut, accounts, Some(0))
+            .await,
+        InstructionError::IncorrectProgramId,
+    );
+
+    table.cash_out(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn authority_instructions_need_the_tables_authority() {
+    let mut table = TestTable::seated(2).await;
+    table.shuffle().await.unwrap();
+    let (player, game_state) = (table.players[0].keypair.pubkey(), table.game_state);
+
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::StartRound,
+                vec![AccountMeta::new(player, true), AccountMeta::new(game_state, false)],
+                Some(0),
+            )
+            .await,
+        PokerError::Unauthorized,
+    );
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::SetRandomnessOracle { oracle: None },
+                vec![AccountMeta::new(player, true), AccountMeta::new(game_state, false)],
+                Some(0),
+            )
+            .await,
+        PokerError::Unauthorized,
+    );
+
+    table.start_round_only().await.unwrap();
+    table.deal().await.unwrap();
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+
+    // Settling against a vault the table does not own
+    let other_pot = table.add_token_account(table.mint, player, 0);
+    let authority = table.authority();
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::EndRound,
+                vec![
+                    AccountMeta::new_readonly(authority, true),
+                    AccountMeta::new(game_state, false),
+                    AccountMeta::new_readonly(other_pot, false),
+                ],
+                None,
+            )
+            .await,
+        PokerError::InvalidPotAccount,
+    );
+    table.end_round().await.unwrap();
+}
+
+#[tokio::test]
+async fn tables_are_created_for_real_mints_only() {
+    let mut table = TestTable::new(2).await;
+
+    // A token account is owned by the token program but is not a mint
+    let not_a_mint = table.players[0].token_account;
+    let accounts = table.initialize_accounts(table.game_state, not_a_mint);
+    assert_poker_error(
+        table
+            .send_instruction(
+                PokerInstruction::InitializeGame {
+                    max_players: 2,
+                    table_id: TABLE_ID,
+                    config: table_config(),
+                },
+                accounts,
+                None,
+            )
+            .await,
+        PokerError::InvalidMint,
+    );
+
+    let mut accounts = table.initialize_accounts(table.game_state, table.mint);
+    accounts[7] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    assert_instruction_error(
+        table
+            .send_instruction(
+                PokerInstruction::InitializeGame {
+                    max_players: 2,
+                    table_id: TABLE_ID,
+                    config: table_config(),
+                },
+                accounts,
+                None,
+            )
+            .await,
+        InstructionError::IncorrectProgramId,
+    );
+
+    table.initialize(2).await.unwrap();
+}
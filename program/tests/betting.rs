@@ -0,0 +1,58 @@
+mod common;
+
+use common::{assert_poker_error, TestTable};
+use solana_poker::{GameStage, PokerError};
+
+/// Three seated players with the preflop action checked through to the flop
+async fn table_on_the_flop() -> TestTable {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+    table
+}
+
+#[tokio::test]
+async fn check_is_rejected_when_facing_a_bet() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    assert_poker_error(table.check(0).await, PokerError::CannotCheck);
+}
+
+#[tokio::test]
+async fn call_is_rejected_when_there_is_nothing_to_call() {
+    let mut table = table_on_the_flop().await;
+
+    assert_poker_error(table.call(1).await, PokerError::NothingToCall);
+    table.check(1).await.unwrap();
+}
+
+#[tokio::test]
+async fn bet_opens_a_street_and_raise_needs_a_bet() {
+    let mut table = table_on_the_flop().await;
+    let big_blind = table.game_state().await.config.big_blind;
+
+    assert_poker_error(table.raise(1, big_blind).await, PokerError::NoBetToRaise);
+    assert_poker_error(table.bet(1, big_blind - 1).await, PokerError::BetTooSmall);
+    table.bet(1, big_blind).await.unwrap();
+
+    assert_poker_error(table.bet(2, big_blind).await, PokerError::BetAlreadyMade);
+    table.raise(2, big_blind).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.current_bet, 2 * big_blind);
+    assert_eq!(state.current_player_turn, 0);
+}
+
+#[tokio::test]
+async fn bet_is_rejected_preflop_because_the_blinds_are_a_bet() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+    let big_blind = table.game_state().await.config.big_blind;
+
+    assert_poker_error(table.bet(0, big_blind).await, PokerError::BetAlreadyMade);
+    table.raise(0, big_blind).await.unwrap();
+}
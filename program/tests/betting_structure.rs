@@ -0,0 +1,83 @@
+mod common;
+
+use common::{assert_poker_error, table_config, TestTable, BUY_IN};
+use solana_poker::{BettingStructure, GameStage, PokerError, TableConfig};
+
+/// Three players bought in at a table with `config`
+async fn seated_with(config: TableConfig) -> TestTable {
+    let mut table = TestTable::new(3).await;
+    table.initialize_with(3, config).await.unwrap();
+    for player in 0..3 {
+        table.buy_in(player, BUY_IN).await.unwrap();
+    }
+    table
+}
+
+#[tokio::test]
+async fn pot_limit_caps_raises_at_the_pot_after_calling() {
+    let config = TableConfig {
+        betting_structure: BettingStructure::PotLimit,
+        ..table_config()
+    };
+    let big_blind = config.big_blind;
+    let mut table = seated_with(config).await;
+    table.start_round().await.unwrap();
+
+    // 1.5 big blinds in the pot plus 1 to call
+    assert_eq!(table.game_state().await.max_raise(0), 5 * big_blind / 2);
+    assert_poker_error(table.raise(0, 5 * big_blind / 2 + 1).await, PokerError::BetTooLarge);
+    table.raise(0, 5 * big_blind / 2).await.unwrap();
+
+    // Going all-in for more than the pot is over the limit too
+    assert_poker_error(table.all_in(1).await, PokerError::BetTooLarge);
+    table.call(1).await.unwrap();
+    table.call(2).await.unwrap();
+
+    // On the flop a bet is capped at the pot
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    let pot = state.pot_total;
+    assert_eq!(pot, 3 * 7 * big_blind / 2);
+    assert_poker_error(table.bet(1, pot + 1).await, PokerError::BetTooLarge);
+    table.bet(1, pot).await.unwrap();
+}
+
+#[tokio::test]
+async fn fixed_limit_bets_one_size_a_street_with_capped_raises() {
+    let config = TableConfig {
+        betting_structure: BettingStructure::FixedLimit,
+        max_raises: 2,
+        ..table_config()
+    };
+    let big_blind = config.big_blind;
+    let mut table = seated_with(config).await;
+    table.start_round().await.unwrap();
+
+    // Preflop raises are one small bet, the big blind
+    assert_poker_error(table.raise(0, 2 * big_blind).await, PokerError::BetTooLarge);
+    table.raise(0, big_blind).await.unwrap();
+    table.raise(1, big_blind).await.unwrap();
+
+    // Two raises cap the street, so the big blind can only call or fold
+    assert_poker_error(table.raise(2, big_blind).await, PokerError::RaiseCapReached);
+    assert_poker_error(table.all_in(2).await, PokerError::RaiseCapReached);
+    table.call(2).await.unwrap();
+    table.call(0).await.unwrap();
+
+    // The flop is bet in small bets too
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+    assert_poker_error(table.bet(1, 2 * big_blind).await, PokerError::BetTooLarge);
+    table.bet(1, big_blind).await.unwrap();
+    table.call(2).await.unwrap();
+    table.call(0).await.unwrap();
+
+    // From the turn on bets are a big bet, and the raise count starts over
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Turn);
+    assert_eq!(state.raises_this_street, 0);
+    assert_poker_error(table.bet(1, big_blind).await, PokerError::BetTooSmall);
+    table.bet(1, 2 * big_blind).await.unwrap();
+    assert_poker_error(table.raise(2, big_blind).await, PokerError::RaiseTooSmall);
+    table.raise(2, 2 * big_blind).await.unwrap();
+    assert_eq!(table.game_state().await.current_bet, 4 * big_blind);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{table_config, TestTable, BUY_IN};
+use solana_poker::{GameStage, TableConfig};
+
+/// Stakes with a dead button
+fn dead_button_config() -> TableConfig {
+    TableConfig {
+        dead_button: true,
+        ..table_config()
+    }
+}
+
+#[tokio::test]
+async fn heads_up_the_button_posts_the_small_blind() {
+    let mut table = TestTable::seated(2).await;
+    let config = table_config();
+
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealer_index, 0);
+    assert_eq!(state.seat(0).unwrap().current_bet, config.small_blind);
+    assert_eq!(state.seat(1).unwrap().current_bet, config.big_blind);
+
+    // The button acts first preflop and last after the flop
+    assert_eq!(state.current_player_turn, 0);
+    table.call(0).await.unwrap();
+    table.check(1).await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.current_player_turn, 1);
+
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // The blinds swap with the button
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealer_index, 1);
+    assert_eq!(state.seat(1).unwrap().current_bet, config.small_blind);
+    assert_eq!(state.seat(0).unwrap().current_bet, config.big_blind);
+}
+
+#[tokio::test]
+async fn a_dead_button_moves_the_big_blind_one_seat_a_hand() {
+    let mut table = TestTable::new(4).await;
+    table.initialize_with(4, dead_button_config()).await.unwrap();
+    for player in 0..4 {
+        table.buy_in(player, BUY_IN).await.unwrap();
+    }
+    let config = table_config();
+
+    // Button on seat 0, blinds on 1 and 2, and everyone folds to the big blind
+    table.start_round().await.unwrap();
+    table.fold(3).await.unwrap();
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // With the big blind gone, the small blind on its seat is dead and last
+    // hand's small blind gets the button
+    table.cash_out(2).await.unwrap();
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealer_index, 1);
+    assert_eq!(state.seat(3).unwrap().current_bet, config.big_blind);
+    assert_eq!(state.pot_total, config.big_blind);
+    assert_eq!(state.current_player_turn, 0);
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // Next the button is dead on the empty seat
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealer_index, 2);
+    assert_eq!(state.seat(3).unwrap().current_bet, config.small_blind);
+    assert_eq!(state.seat(0).unwrap().current_bet, config.big_blind);
+    assert_eq!(state.current_player_turn, 1);
+}
+
+#[tokio::test]
+async fn players_back_from_sitting_out_post_the_big_blind_they_missed() {
+    let mut table = TestTable::seated(3).await;
+    let config = table_config();
+
+    // Seat 1 sits out a hand in which the big blind passes them
+    table.sit_out(1).await.unwrap();
+    table.start_round().await.unwrap();
+    table.fold(0).await.unwrap();
+    table.showdown().await.unwrap();
+    assert!(table.game_state().await.seat(1).unwrap().missed_big_blind);
+
+    // Back on the button, they post a live big blind on top of the blinds
+    table.sit_in(1).await.unwrap();
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealer_index, 1);
+    let seat = state.seat(1).unwrap();
+    assert_eq!(seat.current_bet, config.big_blind);
+    assert!(!seat.missed_big_blind);
+    assert_eq!(state.pot_total, config.small_blind + 2 * config.big_blind);
+
+    // Having posted, they only need to check
+    assert_eq!(state.current_player_turn, 1);
+    table.check(1).await.unwrap();
+    table.call(2).await.unwrap();
+    table.check(0).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+}
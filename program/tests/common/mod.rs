@@ -0,0 +1,1010 @@
+#![allow(dead_code)]
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_poker::{
+    hand_eval::Card, mock_oracle, process_instruction, randomness::VrfResult, shuffle, sra,
+    BettingStructure, GameStage, GameState, PokerError, PokerInstruction, TableConfig,
+    ENCRYPT_CHUNK, GAME_SEED,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+    sysvar::clock::Clock,
+};
+use solana_program_test::{
+    processor, BanksClientError, BanksTransactionResultWithMetadata, ProgramTest,
+    ProgramTestContext,
+};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use spl_token_2022::{extension::StateWithExtensions, state::Account as Token2022Account};
+
+pub const TABLE_ID: u64 = 1;
+pub const STARTING_BALANCE: u64 = 10_000_000;
+pub const BUY_IN: u64 = 1_000_000;
+
+const POT_SEED: &[u8] = b"poker_pot";
+
+/// Stakes the helpers' tables play: 0.025/0.05 on a 6 decimal mint with
+/// buy-ins from 10 to 100 big blinds, no ante and no rake
+pub fn table_config() -> TableConfig {
+    TableConfig {
+        small_blind: 25_000,
+        big_blind: 50_000,
+        ante: None,
+        min_buy_in_bbs: 10,
+        max_buy_in_bbs: 100,
+        decimals: 6,
+        rake_bps: 0,
+        rake_cap_bbs: 0,
+        no_flop_no_drop: false,
+        dead_button: false,
+        betting_structure: BettingStructure::NoLimit,
+        max_raises: 0,
+    }
+}
+
+/// `table_config` in lamports, which have 9 decimals
+pub fn native_config() -> TableConfig {
+    TableConfig {
+        decimals: 9,
+        ..table_config()
+    }
+}
+
+/// `BuyIn` for `amount` into the first empty seat
+pub fn buy_in_instruction(amount: u64) -> PokerInstruction {
+    PokerInstruction::BuyIn { amount, seat: None }
+}
+
+/// Shuffle seed the helpers reveal for `player`
+pub fn seed_for(player: usize) -> [u8; 32] {
+    [player as u8 + 1; 32]
+}
+
+/// A valid group element that is no card, for players who post garbage
+pub fn not_a_card() -> sra::Point {
+    sra::encrypt(sra::encode(0), sra::key_from_bytes(&[0xff; 64]))
+}
+
+/// Parse cards like "As Kd 10h 2c"
+pub fn cards(hand: &str) -> Vec<Card> {
+    hand.split_whitespace()
+        .map(|card| {
+            let (rank, suit) = card.split_at(card.len() - 1);
+            let rank = match rank {
+                "J" => 9,
+                "Q" => 10,
+                "K" => 11,
+                "A" => 12,
+                n => n.parse::<u8>().unwrap() - 2,
+            };
+            let suit = match suit {
+                "h" => 0,
+                "d" => 1,
+                "c" => 2,
+                "s" => 3,
+                _ => panic!("bad suit in {}", card),
+            };
+            suit * 13 + rank
+        })
+        .collect()
+}
+
+/// Assert that a transaction failed with the given runtime error
+pub fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, expected)
+    );
+}
+
+/// Assert that a transaction failed with the given program error
+pub fn assert_poker_error(result: Result<(), BanksClientError>, expected: PokerError) {
+    match result.unwrap_err().unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            assert_eq!(PokerError::from_program_error(&ProgramError::Custom(code)), Some(expected));
+        }
+        other => panic!("expected {:?}, got {:?}", expected, other),
+    }
+}
+
+pub struct TestPlayer {
+    pub keypair: Keypair,
+    pub token_account: Pubkey,
+    pub encryption_key: sra::Key,
+    pub decryption_key: sra::Key,
+}
+
+pub struct TestTable {
+    pub context: ProgramTestContext,
+    pub program_id: Pubkey,
+    pub game_state: Pubkey,
+    pub pot: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub players: Vec<TestPlayer>,
+    pub oracle: Pubkey,
+    pub randomness: Pubkey,
+}
+
+/// PDA that owns the vault of `game_state`
+pub fn pot_authority(program_id: &Pubkey, game_state: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POT_SEED, game_state.as_ref()], program_id).0
+}
+
+fn packed<T: Pack>(state: T) -> Vec<u8> {
+    let mut data = vec![0; T::LEN];
+    T::pack(state, &mut data).unwrap();
+    data
+}
+
+fn token_account(token_program: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    Account {
+        lamports: 1_000_000_000,
+        data: packed(TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        }),
+        owner: token_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+impl TestTable {
+    /// Start a validator with a 6 decimal SPL Token mint, `num_players`
+    /// funded wallets and the mock randomness oracle with one unfulfilled
+    /// result account. `game_state` and `pot` are the addresses table
+    /// `TABLE_ID` will be created at.
+    pub async fn new(num_players: usize) -> Self {
+        Self::with_token_program(num_players, spl_token::id()).await
+    }
+
+    /// `new` with the mint and wallets owned by `token_program`
+    pub async fn with_token_program(num_players: usize, token_program: Pubkey) -> Self {
+        Self::start(num_players, token_program).await
+    }
+
+    /// `new` for a native SOL table: players buy in from their wallets,
+    /// which double as their token accounts
+    pub async fn native(num_players: usize) -> Self {
+        Self::start(num_players, system_program::id()).await
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.token_program == system_program::id()
+    }
+
+    async fn start(num_players: usize, token_program: Pubkey) -> Self {
+        let native = token_program == system_program::id();
+        let program_id = Pubkey::new_unique();
+        let mut program_test =
+            ProgramTest::new("solana_poker", program_id, processor!(process_instruction));
+        // `cargo test-sbf --features test-sbf` runs the suite against the
+        // deployed build, with real syscalls and compute metering
+        program_test.prefer_bpf(cfg!(feature = "test-sbf"));
+
+        let mint = if native {
+            spl_token::native_mint::id()
+        } else {
+            Pubkey::new_unique()
+        };
+        if !native {
+            program_test.add_account(
+                mint,
+                Account {
+                    lamports: 1_000_000_000,
+                    data: packed(Mint {
+                        mint_authority: COption::Some(Pubkey::new_unique()),
+                        supply: STARTING_BALANCE * num_players as u64,
+                        decimals: 6,
+                        is_initialized: true,
+                        freeze_authority: COption::None,
+                    }),
+                    owner: token_program,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+
+        let oracle = Pubkey::new_unique();
+        program_test.add_program(
+            "mock_oracle",
+            oracle,
+            processor!(mock_oracle::process_instruction),
+        );
+        let randomness = Pubkey::new_unique();
+        program_test.add_account(
+            randomness,
+            Account {
+                lamports: 1_000_000_000,
+                data: vec![0; VrfResult::LEN],
+                owner: oracle,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let mut players = Vec::new();
+        for i in 0..num_players {
+            let keypair = Keypair::new();
+            program_test.add_account(
+                keypair.pubkey(),
+                Account {
+                    lamports: 1_000_000_000,
+                    data: vec![],
+                    owner: system_program::id(),
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+            let token_account_key = if native {
+                keypair.pubkey()
+            } else {
+                let key = Pubkey::new_unique();
+                program_test.add_account(
+                    key,
+                    token_account(token_program, mint, keypair.pubkey(), STARTING_BALANCE),
+                );
+                key
+            };
+            let encryption_key = sra::key_from_bytes(&[i as u8 + 1; 64]);
+            players.push(TestPlayer {
+                keypair,
+                token_account: token_account_key,
+                encryption_key,
+                decryption_key: sra::decryption_key(encryption_key).unwrap(),
+            });
+        }
+
+        let context = program_test.start_with_context().await;
+
+        // The game state address depends on the payer, which is only known
+        // once the validator is up
+        let (game_state, _) = Pubkey::find_program_address(
+            &[GAME_SEED, context.payer.pubkey().as_ref(), &TABLE_ID.to_le_bytes()],
+            &program_id,
+        );
+        let pot_authority = pot_authority(&program_id, &game_state);
+        let pot = if native {
+            pot_authority
+        } else {
+            get_associated_token_address_with_program_id(&pot_authority, &mint, &token_program)
+        };
+
+        Self {
+            context,
+            program_id,
+            game_state,
+            pot,
+            mint,
+            token_program,
+            players,
+            oracle,
+            randomness,
+        }
+    }
+
+    /// Initialize the table and buy every player in for `BUY_IN`
+    pub async fn seated(num_players: usize) -> Self {
+        let mut table = Self::new(num_players).await;
+        table.initialize(num_players as u8).await.unwrap();
+        for i in 0..num_players {
+            table.buy_in(i, BUY_IN).await.unwrap();
+        }
+        table
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    fn instruction(&self, data: PokerInstruction, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: data.try_to_vec().unwrap(),
+        }
+    }
+
+    async fn transaction(&mut self, instruction: Instruction, signer: Option<usize>) -> Transaction {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let payer = &self.context.payer;
+        let mut signers: Vec<&Keypair> = vec![payer];
+        if let Some(i) = signer {
+            signers.push(&self.players[i].keypair);
+        }
+        Transaction::new_signed_with_payer(&[instruction], Some(&payer.pubkey()), &signers, blockhash)
+    }
+
+    async fn send(
+        &mut self,
+        instruction: Instruction,
+        signer: Option<usize>,
+    ) -> Result<(), BanksClientError> {
+        let transaction = self.transaction(instruction, signer).await;
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Send a transaction that must succeed and return its program logs
+    async fn send_for_logs(&mut self, instruction: Instruction, signer: Option<usize>) -> Vec<String> {
+        let result = self.send_for_metadata(instruction, signer).await;
+        result.metadata.unwrap().log_messages
+    }
+
+    /// Send a transaction that must succeed and return what it did
+    async fn send_for_metadata(
+        &mut self,
+        instruction: Instruction,
+        signer: Option<usize>,
+    ) -> BanksTransactionResultWithMetadata {
+        let transaction = self.transaction(instruction, signer).await;
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        result.result.clone().unwrap();
+        result
+    }
+
+    pub async fn initialize(&mut self, max_players: u8) -> Result<(), BanksClientError> {
+        let config = if self.is_native() {
+            native_config()
+        } else {
+            table_config()
+        };
+        self.initialize_with(max_players, config).await
+    }
+
+    pub async fn initialize_with(
+        &mut self,
+        max_players: u8,
+        config: TableConfig,
+    ) -> Result<(), BanksClientError> {
+        let game_state = self.game_state;
+        self.initialize_table(max_players, TABLE_ID, game_state, config)
+            .await
+    }
+
+    /// Send InitializeGame for `table_id` with `game_state` as the game
+    /// state account
+    pub async fn initialize_table(
+        &mut self,
+        max_players: u8,
+        table_id: u64,
+        game_state: Pubkey,
+        config: TableConfig,
+    ) -> Result<(), BanksClientError> {
+        if self.is_native() {
+            let escrow = pot_authority(&self.program_id, &game_state);
+            let accounts = vec![
+                AccountMeta::new(self.authority(), true),
+                AccountMeta::new(game_state, false),
+                AccountMeta::new(escrow, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+            ];
+            return self
+                .send_instruction(
+                    PokerInstruction::InitializeNativeGame {
+                        max_players,
+                        table_id,
+                        config,
+                    },
+                    accounts,
+                    None,
+                )
+                .await;
+        }
+
+        let accounts = self.initialize_accounts(game_state, self.mint);
+        self.send_instruction(
+            PokerInstruction::InitializeGame {
+                max_players,
+                table_id,
+                config,
+            },
+            accounts,
+            None,
+        )
+        .await
+    }
+
+    /// InitializeGame accounts for a table at `game_state` playing for `mint`
+    pub fn initialize_accounts(&self, game_state: Pubkey, mint: Pubkey) -> Vec<AccountMeta> {
+        let pot_authority = pot_authority(&self.program_id, &game_state);
+        vec![
+            AccountMeta::new(self.authority(), true),
+            AccountMeta::new(game_state, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&pot_authority, &mint, &self.token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(pot_authority, false),
+            AccountMeta::new_readonly(self.token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(solana_program::sysvar::rent::id(), false),
+        ]
+    }
+
+    /// Buy in, taking the first empty seat if not seated yet
+    pub async fn buy_in(&mut self, player: usize, amount: u64) -> Result<(), BanksClientError> {
+        let accounts = self.buy_in_accounts(player);
+        self.send_instruction(buy_in_instruction(amount), accounts, Some(player))
+            .await
+    }
+
+    pub async fn buy_in_at(&mut self, player: usize, seat: u8, amount: u64) -> Result<(), BanksClientError> {
+        let accounts = self.buy_in_accounts(player);
+        let seat = Some(seat);
+        self.send_instruction(PokerInstruction::BuyIn { amount, seat }, accounts, Some(player))
+            .await
+    }
+
+    pub fn buy_in_accounts(&self, player: usize) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.players[player].keypair.pubkey(), true),
+            AccountMeta::new(self.game_state, false),
+            AccountMeta::new(self.players[player].token_account, false),
+            AccountMeta::new(self.pot, false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new_readonly(self.token_program, false),
+        ]
+    }
+
+    /// Send `data` with exactly `accounts`, signed by the payer and `signer`
+    pub async fn send_instruction(
+        &mut self,
+        data: PokerInstruction,
+        accounts: Vec<AccountMeta>,
+        signer: Option<usize>,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.instruction(data, accounts);
+        self.send(ix, signer).await
+    }
+
+    /// Send raw instruction `data`, as a client with a broken encoder might
+    pub async fn send_data(
+        &mut self,
+        data: Vec<u8>,
+        accounts: Vec<AccountMeta>,
+        signer: Option<usize>,
+    ) -> Result<(), BanksClientError> {
+        let ix = Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        };
+        self.send(ix, signer).await
+    }
+
+    /// Send `instruction` signed only by the payer, as any third party could
+    pub async fn send_as_payer(&mut self, instruction: Instruction) -> Result<(), BanksClientError> {
+        self.send(instruction, None).await
+    }
+
+    /// Send `lamports` from the payer to `address`, which does not have to
+    /// sign for it
+    pub async fn send_lamports(&mut self, address: Pubkey, lamports: u64) {
+        let transfer = system_instruction::transfer(&self.authority(), &address, lamports);
+        self.send_as_payer(transfer).await.unwrap();
+    }
+
+    /// Store `account` at a fresh address
+    pub fn add_account(&mut self, account: Account) -> Pubkey {
+        let address = Pubkey::new_unique();
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+        address
+    }
+
+    /// Copy of the game state account owned by `owner` instead
+    pub async fn spoofed_game_state(&mut self, owner: Pubkey) -> Pubkey {
+        let mut account = self
+            .context
+            .banks_client
+            .get_account(self.game_state)
+            .await
+            .unwrap()
+            .unwrap();
+        account.owner = owner;
+        self.add_account(account)
+    }
+
+    /// Token account for `mint` held by `owner`
+    pub fn add_token_account(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        self.add_account(token_account(self.token_program, mint, owner, amount))
+    }
+
+    /// Run the commit-reveal shuffle with every player holding chips, start
+    /// the hand and deal the hole cards
+    pub async fn start_round(&mut self) -> Result<(), BanksClientError> {
+        self.shuffle().await?;
+        self.start_round_only().await?;
+        self.deal().await
+    }
+
+    /// Start a hand dealing `order` from the top of the deck: two hole cards
+    /// per dealt-in player in seat order, then the board
+    pub async fn start_round_with(&mut self, order: &[Card]) -> Result<(), BanksClientError> {
+        self.shuffle().await?;
+        self.start_round_only().await?;
+        self.encrypt_passes(Some(order)).await?;
+        self.unmask_pending().await
+    }
+
+    /// Commit and reveal a fixed seed for every player with chips who is
+    /// not sat out
+    pub async fn shuffle(&mut self) -> Result<(), BanksClientError> {
+        let state = self.game_state().await;
+        let seated: Vec<usize> = (0..self.players.len())
+            .filter(|&player| {
+                let key = self.players[player].keypair.pubkey();
+                state.seated().any(|p| p.pubkey == key && p.wants_cards())
+            })
+            .collect();
+        for &player in &seated {
+            let commitment = shuffle::commitment(&self.players[player].keypair.pubkey(), &seed_for(player));
+            self.commit_seed(player, commitment).await?;
+        }
+        for &player in &seated {
+            self.reveal_seed(player, seed_for(player)).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn commit_seed(&mut self, player: usize, commitment: [u8; 32]) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::CommitSeed { commitment })
+            .await
+    }
+
+    pub async fn reveal_seed(&mut self, player: usize, seed: [u8; 32]) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::RevealSeed { seed })
+            .await
+    }
+
+    /// Test player sitting in `seat`
+    fn player_in_seat(&self, state: &GameState, seat: usize) -> usize {
+        let key = state.seat(seat).unwrap().pubkey;
+        self.players
+            .iter()
+            .position(|p| p.keypair.pubkey() == key)
+            .unwrap()
+    }
+
+    /// Finish any encryption passes and unmask whatever the current street
+    /// needs
+    pub async fn deal(&mut self) -> Result<(), BanksClientError> {
+        self.encrypt_passes(None).await?;
+        self.unmask_pending().await
+    }
+
+    /// Run the remaining encryption passes. The first pass can still see the
+    /// plaintext deck, so it arranges `order` on top if one is given.
+    pub async fn encrypt_passes(&mut self, order: Option<&[Card]>) -> Result<(), BanksClientError> {
+        loop {
+            let state = self.game_state().await;
+            if state.game_stage == GameStage::Waiting || state.dealt_in() == 0 || state.deck_encrypted() {
+                return Ok(());
+            }
+
+            let seat = state
+                .seated()
+                .find(|p| p.hole_index == Some(state.encryption_passes))
+                .unwrap()
+                .seat_index as usize;
+            let player = self.player_in_seat(&state, seat);
+
+            let mut plaintext: Vec<sra::Point> = state.deck.clone();
+            if let (0, Some(order)) = (state.encryption_passes, order) {
+                let rest = state
+                    .deck
+                    .iter()
+                    .map(|&card| sra::decode(card).unwrap())
+                    .filter(|card| !order.contains(card));
+                plaintext = order.iter().copied().chain(rest).map(sra::encode).collect();
+            }
+
+            let key = &self.players[player];
+            let deck = plaintext[state.encrypted_cards as usize..]
+                .iter()
+                .map(|&card| sra::encrypt(card, key.encryption_key))
+                .collect();
+            let key_commitment =
+                sra::key_commitment(&key.keypair.pubkey(), key.encryption_key, key.decryption_key);
+            self.encrypt_deck(player, deck, key_commitment).await?;
+        }
+    }
+
+    /// Have every dealt-in player unmask the cards the current street needs
+    pub async fn unmask_pending(&mut self) -> Result<(), BanksClientError> {
+        loop {
+            let state = self.game_state().await;
+            if !state.deck_encrypted() || state.cards_revealed >= state.street_step() {
+                return Ok(());
+            }
+
+            let step = state.cards_revealed + 1;
+            let (seat, hole_index) = state
+                .seated()
+                .filter(|p| p.unmask_step < step)
+                .filter_map(|p| p.hole_index.map(|index| (p.seat_index as usize, index)))
+                .min_by_key(|&(_, index)| index)
+                .unwrap();
+            let player = self.player_in_seat(&state, seat);
+
+            let decryption_key = self.players[player].decryption_key;
+            let cards = state
+                .unmask_positions(step, hole_index)
+                .iter()
+                .map(|&position| sra::decrypt(state.deck[position], decryption_key))
+                .collect();
+            self.unmask_cards(player, cards).await?;
+        }
+    }
+
+    /// Post an encryption pass, or the rest of one, in as many `EncryptDeck`
+    /// calls as it takes
+    pub async fn encrypt_deck(
+        &mut self,
+        player: usize,
+        deck: Vec<sra::Point>,
+        key_commitment: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        for cards in deck.chunks(ENCRYPT_CHUNK) {
+            self.encrypt_cards(player, cards.to_vec(), key_commitment).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn encrypt_cards(
+        &mut self,
+        player: usize,
+        cards: Vec<sra::Point>,
+        key_commitment: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::EncryptDeck { cards, key_commitment })
+            .await
+    }
+
+    pub async fn unmask_cards(&mut self, player: usize, cards: Vec<sra::Point>) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::UnmaskCards { cards })
+            .await
+    }
+
+    pub async fn reveal_key(
+        &mut self,
+        player: usize,
+        encryption_key: sra::Key,
+        decryption_key: sra::Key,
+    ) -> Result<(), BanksClientError> {
+        self.player_instruction(
+            player,
+            PokerInstruction::RevealKey {
+                encryption_key,
+                decryption_key,
+            },
+        )
+        .await
+    }
+
+    /// Reveal the key of every player dealt into the hand
+    pub async fn reveal_keys(&mut self) -> Result<(), BanksClientError> {
+        let state = self.game_state().await;
+        let dealt_in: Vec<usize> = state
+            .seated()
+            .filter(|p| p.hole_index.is_some())
+            .map(|p| p.seat_index as usize)
+            .collect();
+        for seat in dealt_in {
+            let player = self.player_in_seat(&state, seat);
+            let (encryption_key, decryption_key) = (
+                self.players[player].encryption_key,
+                self.players[player].decryption_key,
+            );
+            self.reveal_key(player, encryption_key, decryption_key).await?;
+        }
+        Ok(())
+    }
+
+    /// A player's own hole cards, decrypted with their key
+    pub async fn hole_cards(&mut self, player: usize) -> [Card; 2] {
+        let key = self.players[player].keypair.pubkey();
+        let state = self.game_state().await;
+        let index = state
+            .seated()
+            .find(|p| p.pubkey == key)
+            .and_then(|p| p.hole_index)
+            .unwrap() as usize;
+        let decryption_key = self.players[player].decryption_key;
+        let card = |position: usize| sra::decode(sra::decrypt(state.deck[position], decryption_key)).unwrap();
+        [card(2 * index), card(2 * index + 1)]
+    }
+
+    /// Move the validator clock forward by `seconds`
+    pub async fn warp_clock(&mut self, seconds: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn set_randomness_oracle(&mut self, oracle: Option<Pubkey>) -> Result<(), BanksClientError> {
+        let ix = self.instruction(
+            PokerInstruction::SetRandomnessOracle { oracle },
+            vec![
+                AccountMeta::new_readonly(self.authority(), true),
+                AccountMeta::new(self.game_state, false),
+            ],
+        );
+        self.send(ix, None).await
+    }
+
+    /// Publish randomness into the mock oracle's result account
+    pub async fn fulfill_randomness(&mut self, randomness: [u8; 32]) -> Result<(), BanksClientError> {
+        let ix = mock_oracle::fulfill(&self.oracle, &self.randomness, randomness);
+        self.send(ix, None).await
+    }
+
+    /// Send StartRound with `randomness` as the oracle account
+    pub async fn start_round_from(&mut self, randomness: Pubkey) -> Result<(), BanksClientError> {
+        let ix = self.instruction(
+            PokerInstruction::StartRound,
+            vec![
+                AccountMeta::new(self.authority(), true),
+                AccountMeta::new(self.game_state, false),
+                AccountMeta::new_readonly(randomness, false),
+            ],
+        );
+        self.send(ix, None).await
+    }
+
+    /// Send StartRound without shuffling first
+    pub async fn start_round_only(&mut self) -> Result<(), BanksClientError> {
+        let ix = self.instruction(
+            PokerInstruction::StartRound,
+            vec![
+                AccountMeta::new(self.authority(), true),
+                AccountMeta::new(self.game_state, false),
+            ],
+        );
+        self.send(ix, None).await
+    }
+
+    /// Send a betting action, dealing any cards the street is waiting on
+    async fn player_action(
+        &mut self,
+        player: usize,
+        action: PokerInstruction,
+    ) -> Result<(), BanksClientError> {
+        self.deal().await?;
+        self.player_instruction(player, action).await
+    }
+
+    pub async fn player_instruction(
+        &mut self,
+        player: usize,
+        action: PokerInstruction,
+    ) -> Result<(), BanksClientError> {
+        let ix = self.instruction(
+            action,
+            vec![
+                AccountMeta::new(self.players[player].keypair.pubkey(), true),
+                AccountMeta::new(self.game_state, false),
+            ],
+        );
+        self.send(ix, Some(player)).await
+    }
+
+    pub async fn fold(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::Fold).await
+    }
+
+    pub async fn call(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::Call).await
+    }
+
+    pub async fn raise(&mut self, player: usize, amount: u64) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::Raise { amount })
+            .await
+    }
+
+    pub async fn check(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::Check).await
+    }
+
+    pub async fn bet(&mut self, player: usize, amount: u64) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::Bet { amount })
+            .await
+    }
+
+    pub async fn all_in(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_action(player, PokerInstruction::AllIn).await
+    }
+
+    /// Time out whoever the hand is waiting on, signed by `caller`
+    pub async fn timeout_action(&mut self, caller: usize, sit_out: bool) -> Result<(), BanksClientError> {
+        self.player_instruction(caller, PokerInstruction::TimeoutAction { sit_out })
+            .await
+    }
+
+    pub async fn sit_out(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::SitOut).await
+    }
+
+    pub async fn sit_in(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::SitIn).await
+    }
+
+    pub async fn leave_after_hand(&mut self, player: usize) -> Result<(), BanksClientError> {
+        self.player_instruction(player, PokerInstruction::LeaveAfterHand)
+            .await
+    }
+
+    fn end_round_instruction(&self) -> Instruction {
+        self.instruction(
+            PokerInstruction::EndRound,
+            vec![
+                AccountMeta::new_readonly(self.authority(), true),
+                AccountMeta::new(self.game_state, false),
+                AccountMeta::new_readonly(self.pot, false),
+            ],
+        )
+    }
+
+    pub async fn end_round(&mut self) -> Result<(), BanksClientError> {
+        let ix = self.end_round_instruction();
+        self.send(ix, None).await
+    }
+
+    /// Settle the hand and return the program logs
+    pub async fn end_round_logs(&mut self) -> Vec<String> {
+        let ix = self.end_round_instruction();
+        self.send_for_logs(ix, None).await
+    }
+
+    /// Settle the hand and return the compute units it took
+    pub async fn end_round_compute_units(&mut self) -> u64 {
+        let ix = self.end_round_instruction();
+        let result = self.send_for_metadata(ix, None).await;
+        result.metadata.unwrap().compute_units_consumed
+    }
+
+    /// Reveal keys if the hand is contested, then settle it
+    pub async fn showdown(&mut self) -> Result<(), BanksClientError> {
+        let state = self.game_state().await;
+        if state.seated().filter(|p| !p.has_folded).count() > 1 {
+            self.reveal_keys().await?;
+        }
+        self.end_round().await
+    }
+
+    pub async fn cash_out(&mut self, player: usize) -> Result<(), BanksClientError> {
+        let accounts = self.cash_out_accounts(player);
+        self.send_instruction(PokerInstruction::CashOut, accounts, Some(player))
+            .await
+    }
+
+    pub fn cash_out_accounts(&self, player: usize) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.players[player].keypair.pubkey(), true),
+            AccountMeta::new(self.game_state, false),
+            AccountMeta::new(self.pot, false),
+            AccountMeta::new(self.players[player].token_account, false),
+            AccountMeta::new_readonly(pot_authority(&self.program_id, &self.game_state), false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new_readonly(self.token_program, false),
+        ]
+    }
+
+    /// Send WithdrawRake, paying the rake out to `treasury`
+    pub async fn withdraw_rake(&mut self, treasury: Pubkey) -> Result<(), BanksClientError> {
+        let accounts = self.withdraw_rake_accounts(treasury);
+        self.send_instruction(PokerInstruction::WithdrawRake, accounts, None)
+            .await
+    }
+
+    pub fn withdraw_rake_accounts(&self, treasury: Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.authority(), true),
+            AccountMeta::new(self.game_state, false),
+            AccountMeta::new(self.pot, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new_readonly(pot_authority(&self.program_id, &self.game_state), false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new_readonly(self.token_program, false),
+        ]
+    }
+
+    pub async fn game_state(&mut self) -> GameState {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.game_state)
+            .await
+            .unwrap()
+            .unwrap();
+        GameState::deserialize(&mut &account.data[..]).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    /// Overwrite the balance of SPL Token account `address`, as if tokens
+    /// had moved in or out without the program seeing it
+    pub async fn set_token_balance(&mut self, address: Pubkey, amount: u64) {
+        let mut account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut state = TokenAccount::unpack(&account.data).unwrap();
+        state.amount = amount;
+        TokenAccount::pack(state, &mut account.data).unwrap();
+        self.context
+            .set_account(&address, &AccountSharedData::from(account));
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    pub async fn current_turn(&mut self) -> usize {
+        self.game_state().await.current_player_turn as usize
+    }
+
+    /// Check, or call if facing a bet, with whoever holds the action
+    pub async fn check_or_call_current(&mut self) -> Result<(), BanksClientError> {
+        let state = self.game_state().await;
+        let seat = state.current_player_turn as usize;
+        if state.seat(seat).unwrap().current_bet < state.current_bet {
+            self.call(seat).await
+        } else {
+            self.check(seat).await
+        }
+    }
+
+    pub async fn chips(&mut self, player: usize) -> u64 {
+        let key = self.players[player].keypair.pubkey();
+        self.game_state()
+            .await
+            .seated()
+            .find(|p| p.pubkey == key)
+            .map(|p| p.chips)
+            .unwrap_or(0)
+    }
+}
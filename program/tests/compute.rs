@@ -0,0 +1,40 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use common::TestTable;
+use solana_poker::{GameStage, MAX_PLAYERS};
+
+/// Compute budget of an instruction that does not ask for more
+const DEFAULT_COMPUTE_UNITS: u64 = 200_000;
+
+#[tokio::test]
+async fn a_full_table_showdown_fits_the_default_compute_budget() {
+    let players = MAX_PLAYERS as usize;
+    let mut table = TestTable::new(players).await;
+    table.initialize(MAX_PLAYERS).await.unwrap();
+
+    // Every stack is different, so the all-ins split into a side pot per
+    // seat and every hand is shown down
+    for player in 0..players {
+        table.buy_in(player, 500_000 + 100_000 * player as u64).await.unwrap();
+    }
+    table.start_round().await.unwrap();
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        let seat = table.current_turn().await;
+        table.all_in(seat).await.unwrap();
+    }
+    table.reveal_keys().await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.seated().filter(|p| !p.has_folded).count(), players);
+    assert_eq!(state.pots.len(), players);
+
+    let compute_units = table.end_round_compute_units().await;
+    assert!(
+        compute_units < DEFAULT_COMPUTE_UNITS,
+        "EndRound took {} compute units",
+        compute_units
+    );
+    assert_eq!(table.game_state().await.game_stage, GameStage::Waiting);
+}
@@ -0,0 +1,329 @@
+mod common;
+
+use common::{assert_poker_error, cards, not_a_card, TestTable, BUY_IN};
+use solana_poker::{sra, GameStage, PokerError, PokerInstruction, ENCRYPT_CHUNK};
+use solana_sdk::signature::Signer;
+
+/// Seat 0: 2h 7d, seat 1: Kh Kd, seat 2: As Ad, board 3c 8h Jd 4s 5c
+const DEAL: &str = "2h 7d Kh Kd As Ad 3c 8h Jd 4s 5c";
+
+/// Cards `player` would post to honestly unmask the next deal step
+async fn honest_unmask(table: &mut TestTable, player: usize) -> Vec<sra::Point> {
+    let state = table.game_state().await;
+    let seat = state.seat(player).unwrap();
+    let positions = state.unmask_positions(seat.unmask_step + 1, seat.hole_index.unwrap());
+    positions
+        .iter()
+        .map(|&position| sra::decrypt(state.deck[position], table.players[player].decryption_key))
+        .collect()
+}
+
+/// Play the preflop round down to the flop with seat 0 folding
+async fn to_the_flop(table: &mut TestTable) {
+    table.fold(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+}
+
+#[tokio::test]
+async fn hole_cards_are_readable_only_by_their_owner() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round_with(&cards(DEAL)).await.unwrap();
+
+    assert_eq!(table.hole_cards(0).await.to_vec(), cards("2h 7d"));
+    assert_eq!(table.hole_cards(1).await.to_vec(), cards("Kh Kd"));
+    assert_eq!(table.hole_cards(2).await.to_vec(), cards("As Ad"));
+
+    // Each hole card still carries its owner's layer, and the board every layer
+    let state = table.game_state().await;
+    assert_eq!(state.deck.len(), 3 * 2 + 5);
+    assert!(state.deck.iter().all(|&card| sra::decode(card).is_none()));
+    assert!(state.board.is_empty());
+
+    // Someone else's key does not open them
+    let wrong_key = table.players[0].decryption_key;
+    assert_eq!(sra::decode(sra::decrypt(state.deck[2], wrong_key)), None);
+}
+
+#[tokio::test]
+async fn encryption_passes_go_in_dealing_order() {
+    let mut table = TestTable::seated(3).await;
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+
+    let state = table.game_state().await;
+    let commitment = [7; 32];
+    assert_poker_error(
+        table.encrypt_deck(1, state.deck.clone(), commitment).await,
+        PokerError::NotYourTurn,
+    );
+
+    // The pass must be a deck of distinct group elements
+    let mut duplicated = state.deck.clone();
+    duplicated[1] = duplicated[0];
+    assert_poker_error(
+        table.encrypt_deck(0, duplicated, commitment).await,
+        PokerError::InvalidEncryptedCards,
+    );
+    let mut malformed = state.deck.clone();
+    malformed[0] = [0; 32];
+    assert_poker_error(
+        table.encrypt_deck(0, malformed, commitment).await,
+        PokerError::InvalidEncryptedCards,
+    );
+
+    // Nothing can be unmasked or bet until every layer is on
+    assert_poker_error(table.unmask_cards(0, vec![]).await, PokerError::NoCardsPending);
+    assert_poker_error(
+        table.player_instruction(0, PokerInstruction::Fold).await,
+        PokerError::CardsNotDealt,
+    );
+
+    table.deal().await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.deck_encrypted());
+    assert_eq!(state.cards_revealed, 1);
+    assert_eq!(state.encrypted_deck.len(), 11);
+}
+
+#[tokio::test]
+async fn an_encryption_pass_is_posted_in_chunks() {
+    let mut table = TestTable::seated(3).await;
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+
+    let state = table.game_state().await;
+    let key = &table.players[0];
+    let deck: Vec<sra::Point> = state
+        .deck
+        .iter()
+        .map(|&card| sra::encrypt(card, key.encryption_key))
+        .collect();
+    let commitment =
+        sra::key_commitment(&key.keypair.pubkey(), key.encryption_key, key.decryption_key);
+
+    table
+        .encrypt_cards(0, deck[..ENCRYPT_CHUNK].to_vec(), commitment)
+        .await
+        .unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.encrypted_cards as usize, ENCRYPT_CHUNK);
+    assert_eq!(state.encryption_passes, 0);
+
+    // The rest follows on under the same commitment, without repeating a
+    // card or running past the deck
+    let rest = deck[ENCRYPT_CHUNK..].to_vec();
+    assert_poker_error(
+        table.encrypt_cards(0, rest.clone(), [7; 32]).await,
+        PokerError::InvalidEncryptedCards,
+    );
+    let mut repeated = rest.clone();
+    repeated[0] = deck[0];
+    assert_poker_error(
+        table.encrypt_cards(0, repeated, commitment).await,
+        PokerError::InvalidEncryptedCards,
+    );
+    assert_poker_error(
+        table.encrypt_cards(0, deck[ENCRYPT_CHUNK - 1..].to_vec(), commitment).await,
+        PokerError::InvalidEncryptedCards,
+    );
+
+    table.encrypt_cards(0, rest, commitment).await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.encrypted_cards, 0);
+    assert_eq!(state.encryption_passes, 1);
+
+    table.deal().await.unwrap();
+    assert!(table.game_state().await.deck_encrypted());
+}
+
+#[tokio::test]
+async fn each_street_waits_for_its_cards_to_be_unmasked() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round_with(&cards(DEAL)).await.unwrap();
+    to_the_flop(&mut table).await;
+
+    // The flop is not out until every player, folded or not, unmasks it
+    assert_poker_error(
+        table.player_instruction(1, PokerInstruction::Check).await,
+        PokerError::CardsNotDealt,
+    );
+
+    let cards_for_2 = honest_unmask(&mut table, 2).await;
+    assert_poker_error(table.unmask_cards(2, cards_for_2).await, PokerError::NotYourTurn);
+    assert_poker_error(
+        table.unmask_cards(0, vec![]).await,
+        PokerError::InvalidEncryptedCards,
+    );
+
+    for player in 0..3 {
+        let unmasked = honest_unmask(&mut table, player).await;
+        table.unmask_cards(player, unmasked).await.unwrap();
+    }
+    assert_eq!(table.game_state().await.board, cards("3c 8h Jd"));
+    assert_poker_error(table.unmask_cards(0, vec![]).await, PokerError::NoCardsPending);
+
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+    assert_eq!(table.game_state().await.board, cards("3c 8h Jd 4s 5c"));
+
+    // Aces beat kings once the keys come out
+    table.showdown().await.unwrap();
+    let state = table.game_state().await;
+    let big_blind = state.config.big_blind;
+    assert_eq!(state.seat(2).unwrap().hole_cards, Some([cards("As")[0], cards("Ad")[0]]));
+    assert_eq!(table.chips(1).await, BUY_IN - big_blind);
+    assert_eq!(table.chips(2).await, BUY_IN + big_blind);
+}
+
+#[tokio::test]
+async fn swapped_board_card_mucks_the_last_unmasker() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round_with(&cards(DEAL)).await.unwrap();
+    to_the_flop(&mut table).await;
+
+    // Seat 2 unmasks last, sees the flop first and shows 3c as an ace
+    for player in 0..2 {
+        let unmasked = honest_unmask(&mut table, player).await;
+        table.unmask_cards(player, unmasked).await.unwrap();
+    }
+    let mut unmasked = honest_unmask(&mut table, 2).await;
+    unmasked[0] = sra::encode(cards("Ac")[0]);
+    table.unmask_cards(2, unmasked).await.unwrap();
+    assert_eq!(table.game_state().await.board, cards("Ac 8h Jd"));
+
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+    table.showdown().await.unwrap();
+
+    // The real board comes out at showdown and seat 2's aces are mucked
+    let state = table.game_state().await;
+    let big_blind = state.config.big_blind;
+    assert_eq!(state.board, cards("3c 8h Jd 4s 5c"));
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind);
+    assert_eq!(table.chips(2).await, BUY_IN - big_blind);
+}
+
+#[tokio::test]
+async fn undecryptable_board_is_charged_to_the_player_who_broke_it() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round_with(&cards(DEAL)).await.unwrap();
+    to_the_flop(&mut table).await;
+    let state = table.game_state().await;
+    let dealer = state.dealer_index;
+    let big_blind = state.config.big_blind;
+
+    for player in 0..2 {
+        let unmasked = honest_unmask(&mut table, player).await;
+        table.unmask_cards(player, unmasked).await.unwrap();
+    }
+    table.unmask_cards(2, vec![not_a_card(); 3]).await.unwrap();
+
+    // Nobody is charged until the keys show whose unmask broke the board
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Showdown);
+    assert!(state.board_disputed);
+    assert_poker_error(table.end_round().await, PokerError::KeysNotRevealed);
+    table.reveal_keys().await.unwrap();
+    table.end_round().await.unwrap();
+
+    // Seat 2 loses their big blind to the others dealt in, and the hand is
+    // replayed with the same button
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.dealer_index, dealer);
+    assert_eq!(state.pot_total, 0);
+    assert_eq!(table.chips(0).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(2).await, BUY_IN - big_blind);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn garbage_unmasked_by_a_folded_player_is_charged_to_them() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round_with(&cards(DEAL)).await.unwrap();
+    let big_blind = table.game_state().await.config.big_blind;
+
+    // Everyone sees the flop, then seat 0 folds
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.check(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.fold(0).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Turn);
+
+    // Seat 0 has nothing left to play for and posts garbage for the turn,
+    // which the others unmask in good faith
+    table.unmask_cards(0, vec![not_a_card()]).await.unwrap();
+    for player in 1..3 {
+        let unmasked = honest_unmask(&mut table, player).await;
+        table.unmask_cards(player, unmasked).await.unwrap();
+    }
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Showdown);
+    assert!(state.board_disputed);
+
+    // The folded seat owes their key too
+    for player in 1..3 {
+        let (encryption_key, decryption_key) =
+            (table.players[player].encryption_key, table.players[player].decryption_key);
+        table.reveal_key(player, encryption_key, decryption_key).await.unwrap();
+    }
+    assert_poker_error(table.end_round().await, PokerError::KeysNotRevealed);
+    table.reveal_keys().await.unwrap();
+    table.end_round().await.unwrap();
+
+    // Seat 0's key exposes their unmask, not the last unmasker's
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(table.chips(0).await, BUY_IN - big_blind);
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(2).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn undecodable_board_with_honest_unmasks_is_a_misdeal() {
+    let mut table = TestTable::seated(3).await;
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+
+    // The first dealing position encrypts something that is not a card into
+    // the turn, which nobody's key can be blamed for
+    let state = table.game_state().await;
+    let dealer = state.dealer_index;
+    let first = state.seated().find(|p| p.hole_index == Some(0)).unwrap().seat_index as usize;
+    let mut plaintext = state.deck.clone();
+    plaintext[2 * 3 + 3] = not_a_card();
+    let key = &table.players[first];
+    let deck = plaintext
+        .iter()
+        .map(|&card| sra::encrypt(card, key.encryption_key))
+        .collect();
+    let key_commitment =
+        sra::key_commitment(&key.keypair.pubkey(), key.encryption_key, key.decryption_key);
+    table.encrypt_deck(first, deck, key_commitment).await.unwrap();
+    table.deal().await.unwrap();
+
+    to_the_flop(&mut table).await;
+    table.check(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.unmask_pending().await.unwrap();
+    assert!(table.game_state().await.board_disputed);
+
+    table.reveal_keys().await.unwrap();
+    table.end_round().await.unwrap();
+
+    // Every unmask matches its key, so everyone gets their chips back
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.dealer_index, dealer);
+    for player in 0..3 {
+        assert_eq!(table.chips(player).await, BUY_IN);
+    }
+}
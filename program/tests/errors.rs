@@ -0,0 +1,47 @@
+mod common;
+
+use common::{assert_poker_error, TestTable};
+use solana_poker::PokerError;
+use solana_program::{instruction::AccountMeta, program_error::ProgramError};
+use solana_sdk::signature::Signer;
+
+#[test]
+fn error_codes_are_stable_and_decode_back() {
+    // Codes clients already match on must never move
+    assert_eq!(ProgramError::from(PokerError::InvalidInstruction), ProgramError::Custom(0));
+    assert_eq!(ProgramError::from(PokerError::GameFull), ProgramError::Custom(1));
+    assert_eq!(ProgramError::from(PokerError::PlayerNotFound), ProgramError::Custom(3));
+    assert_eq!(ProgramError::from(PokerError::Unauthorized), ProgramError::Custom(4));
+    assert_eq!(ProgramError::from(PokerError::NotEnoughPlayers), ProgramError::Custom(8));
+    assert_eq!(ProgramError::from(PokerError::HandInProgress), ProgramError::Custom(9));
+
+    // Every code up to the last variant decodes to the error it came from
+    let last = PokerError::RaiseCapReached as u32;
+    for code in 0..=last {
+        let error = PokerError::from_program_error(&ProgramError::Custom(code))
+            .unwrap_or_else(|| panic!("code {} does not decode", code));
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        assert!(!error.to_string().is_empty());
+    }
+
+    assert_eq!(PokerError::from_program_error(&ProgramError::Custom(last + 1)), None);
+    assert_eq!(PokerError::from_program_error(&ProgramError::InvalidAccountData), None);
+}
+
+#[tokio::test]
+async fn undecodable_instruction_data_is_an_invalid_instruction() {
+    let mut table = TestTable::seated(2).await;
+    let accounts = vec![
+        AccountMeta::new(table.players[0].keypair.pubkey(), true),
+        AccountMeta::new(table.game_state, false),
+    ];
+
+    assert_poker_error(
+        table.send_data(vec![u8::MAX], accounts.clone(), Some(0)).await,
+        PokerError::InvalidInstruction,
+    );
+    assert_poker_error(
+        table.send_data(Vec::new(), accounts, Some(0)).await,
+        PokerError::InvalidInstruction,
+    );
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::cards;
+use solana_poker::hand_eval::{compare, evaluate, Card, HandCategory, DECK_SIZE};
+use std::cmp::Ordering;
+
+fn category(hand: &str) -> HandCategory {
+    evaluate(&cards(hand)).unwrap().category()
+}
+
+fn beats(winner: &str, loser: &str) {
+    let a = evaluate(&cards(winner)).unwrap();
+    let b = evaluate(&cards(loser)).unwrap();
+    assert!(a > b, "{} should beat {}", winner, loser);
+}
+
+fn chops(a: &str, b: &str) {
+    assert_eq!(
+        evaluate(&cards(a)).unwrap(),
+        evaluate(&cards(b)).unwrap(),
+        "{} should chop with {}",
+        a,
+        b
+    );
+}
+
+#[test]
+fn recognizes_every_category_from_seven_cards() {
+    use HandCategory::*;
+
+    assert_eq!(category("As Ks Qs Js 10s 2d 3c"), StraightFlush);
+    assert_eq!(category("5h 4h 3h 2h Ah Kd Kc"), StraightFlush);
+    assert_eq!(category("9c 9d 9h 9s 2d 3c 4h"), FourOfAKind);
+    assert_eq!(category("9c 9d 9h 2s 2d 3c 4h"), FullHouse);
+    assert_eq!(category("9c 9d 9h 2s 2d 2c 4h"), FullHouse);
+    assert_eq!(category("Kc 9c 7c 4c 2c Kd Kh"), Flush);
+    assert_eq!(category("10c 9d 8h 7s 6d 2c 2h"), Straight);
+    assert_eq!(category("Ac 2d 3h 4s 5d Kc Qh"), Straight);
+    assert_eq!(category("9c 9d 9h Ks 2d 3c 5h"), ThreeOfAKind);
+    assert_eq!(category("9c 9d 4h 4s 2d 2c Kh"), TwoPair);
+    assert_eq!(category("9c 9d 4h 6s 2d Qc Kh"), OnePair);
+    assert_eq!(category("9c Jd 4h 6s 2d Qc Kh"), HighCard);
+}
+
+#[test]
+fn rejects_invalid_input() {
+    assert!(evaluate(&cards("As Ks Qs Js")).is_none());
+    assert!(evaluate(&cards("As Ks Qs Js 10s 9s 8s 7s")).is_none());
+    assert!(evaluate(&cards("As As Qs Js 10s")).is_none());
+    assert!(evaluate(&[0, 1, 2, 3, DECK_SIZE]).is_none());
+}
+
+#[test]
+fn breaks_ties_within_categories() {
+    // Straights rank by top card; the wheel is the lowest
+    beats("6c 5d 4h 3s 2d", "Ac 2d 3h 4s 5d");
+    beats("Ac Kd Qh Js 10d", "Kc Qd Jh 10s 9d");
+    // Straight flush beats quads; higher straight flush wins
+    beats("9h 8h 7h 6h 5h", "Ac Ad Ah As Kd");
+    beats("10h 9h 8h 7h 6h", "9s 8s 7s 6s 5s");
+    // Quads compare quad rank, then kicker
+    beats("Kc Kd Kh Ks 2d", "Qc Qd Qh Qs Ad");
+    beats("Kc Kd Kh Ks 3d", "Kc Kd Kh Ks 2d");
+    // Full houses compare trips before the pair
+    beats("3c 3d 3h 2s 2d", "2c 2d 2h As Ad");
+    beats("Ac Ad Ah 3s 3d", "Ac Ad Ah 2s 2d");
+    // Flushes compare every card
+    beats("Ac Jc 9c 7c 3c", "Ad Jd 9d 7d 2d");
+    // Trips, two pair and pairs fall through to kickers
+    beats("7c 7d 7h Ks 2d", "7c 7d 7h Qs Jd");
+    beats("Kc Kd 2h 2s 3d", "Qc Qd Jh Js Ad");
+    beats("Kc Kd 3h 3s 2d", "Kc Kd 2h 2s Ad");
+    beats("Kc Kd 3h 3s 5d", "Kc Kd 3h 3s 4d");
+    beats("Ac Ad 9h 5s 3d", "Ac Ad 9h 5s 2d");
+    beats("Ac Kd 9h 5s 3d", "Ac Kd 9h 5s 2d");
+    // Suits never break ties
+    chops("Ac Kc Qh Js 9d", "Ad Kd Qc Jh 9s");
+}
+
+#[test]
+fn uses_the_best_five_of_seven() {
+    // Third pair cannot be used; the ace kicker plays
+    beats("Kc Kd Qh Qs 2c 2d Ah", "Kc Kd Qh Qs 3c 3d Jh");
+    // Two sets of trips make the best full house
+    chops("9c 9d 9h 8s 8d 8c 2h", "9c 9d 9h 8s 8d 2c 3h");
+    // A six-card straight plays its top five
+    chops("9c 8d 7h 6s 5d 4c 2h", "9c 8d 7h 6s 5d Kc Kh");
+    // Straight flush is found even when a higher plain straight exists
+    assert_eq!(
+        evaluate(&cards("9h 8h 7h 6h 5h 10c Jd")).unwrap().category(),
+        HandCategory::StraightFlush
+    );
+}
+
+#[test]
+fn compares_players_on_a_shared_board() {
+    let board: [Card; 5] = cards("Ah Kh 7d 7c 2s").try_into().unwrap();
+    let aces: [Card; 2] = cards("Ac 3d").try_into().unwrap();
+    let kings: [Card; 2] = cards("Kc Qd").try_into().unwrap();
+    let board_plays_a: [Card; 2] = cards("3c 4d").try_into().unwrap();
+    let board_plays_b: [Card; 2] = cards("3h 4s").try_into().unwrap();
+    let overlaps_board: [Card; 2] = cards("Ah 3s").try_into().unwrap();
+
+    assert_eq!(compare(&aces, &kings, &board), Some(Ordering::Greater));
+    assert_eq!(compare(&board_plays_a, &board_plays_b, &board), Some(Ordering::Equal));
+    assert_eq!(compare(&aces, &overlaps_board, &board), None);
+}
+
+/// Every five-card hand, checked against the textbook category counts
+#[test]
+fn exhaustive_five_card_category_counts() {
+    let mut counts = [0u32; 9];
+    for a in 0..DECK_SIZE {
+        for b in a + 1..DECK_SIZE {
+            for c in b + 1..DECK_SIZE {
+                for d in c + 1..DECK_SIZE {
+                    for e in d + 1..DECK_SIZE {
+                        let rank = evaluate(&[a, b, c, d, e]).unwrap();
+                        counts[rank.category() as usize] += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    assert_eq!(
+        counts,
+        [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 40]
+    );
+}
+
+/// Seven-card evaluation must equal the best of its 21 five-card subsets
+#[test]
+fn seven_card_evaluation_matches_best_of_21() {
+    let mut seed: u64 = 0x5eed;
+    let mut next = || {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (seed >> 33) as u8
+    };
+
+    for _ in 0..20_000 {
+        let mut hand: Vec<Card> = Vec::new();
+        while hand.len() < 7 {
+            let card = next() % DECK_SIZE;
+            if !hand.contains(&card) {
+                hand.push(card);
+            }
+        }
+
+        let mut best = None;
+        for skip_a in 0..7 {
+            for skip_b in skip_a + 1..7 {
+                let five: Vec<Card> = (0..7)
+                    .filter(|&i| i != skip_a && i != skip_b)
+                    .map(|i| hand[i])
+                    .collect();
+                best = best.max(evaluate(&five));
+            }
+        }
+
+        assert_eq!(evaluate(&hand), best, "hand {:?}", hand);
+    }
+}
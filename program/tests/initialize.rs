@@ -0,0 +1,166 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::{
+    assert_instruction_error, assert_poker_error, pot_authority, table_config, TestTable, BUY_IN,
+    TABLE_ID,
+};
+use solana_poker::{GameState, PokerError, SidePot, GAME_SEED, MAX_NAME_LEN, MAX_PLAYERS};
+use solana_program::{instruction::InstructionError, program_pack::Pack, pubkey::Pubkey};
+use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_token::state::Account as TokenAccount;
+
+#[tokio::test]
+async fn game_state_is_created_rent_exempt_at_the_table_pda() {
+    let mut table = TestTable::new(3).await;
+    table.initialize(3).await.unwrap();
+
+    let account = table
+        .context
+        .banks_client
+        .get_account(table.game_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = table.context.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.owner, table.program_id);
+    assert_eq!(account.data.len(), GameState::space(3));
+    assert_eq!(account.lamports, rent.minimum_balance(GameState::space(3)));
+
+    let state = table.game_state().await;
+    assert_eq!(state.table_id, TABLE_ID);
+    assert_eq!(state.max_players, 3);
+
+    // The vault is the pot authority's token account for the table's mint
+    let pot_authority = pot_authority(&table.program_id, &table.game_state);
+    assert_eq!(state.pot_token_account, table.pot);
+    assert_eq!(state.pot_authority, pot_authority);
+    let vault = table
+        .context
+        .banks_client
+        .get_account(table.pot)
+        .await
+        .unwrap()
+        .unwrap();
+    let vault = TokenAccount::unpack(&vault.data).unwrap();
+    assert_eq!(vault.mint, table.mint);
+    assert_eq!(vault.owner, pot_authority);
+    assert_eq!(vault.amount, 0);
+
+    // A table id can only be used once per authority
+    assert_instruction_error(
+        table.initialize(3).await,
+        InstructionError::AccountAlreadyInitialized,
+    );
+}
+
+#[tokio::test]
+async fn tables_can_be_created_at_addresses_funded_in_advance() {
+    let mut table = TestTable::new(2).await;
+
+    // Lamports sent to the table's address before it exists
+    let game_state = table.game_state;
+    table.send_lamports(game_state, 1_000_000).await;
+    table.initialize(2).await.unwrap();
+
+    let account = table
+        .context
+        .banks_client
+        .get_account(game_state)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = table.context.banks_client.get_rent().await.unwrap();
+    assert_eq!(account.owner, table.program_id);
+    assert_eq!(account.data.len(), GameState::space(2));
+    assert_eq!(account.lamports, rent.minimum_balance(GameState::space(2)));
+    assert_eq!(table.game_state().await.max_players, 2);
+}
+
+#[tokio::test]
+async fn tables_take_a_vault_created_in_advance() {
+    let mut table = TestTable::new(2).await;
+
+    // Anyone can create the pot authority's token account first
+    let pot_authority = pot_authority(&table.program_id, &table.game_state);
+    let create_vault = create_associated_token_account(
+        &table.authority(),
+        &pot_authority,
+        &table.mint,
+        &table.token_program,
+    );
+    table.send_as_payer(create_vault).await.unwrap();
+    table.initialize(2).await.unwrap();
+
+    assert_eq!(table.game_state().await.pot_token_account, table.pot);
+    table.buy_in(0, BUY_IN).await.unwrap();
+    assert_eq!(table.token_balance(table.pot).await, BUY_IN);
+}
+
+#[tokio::test]
+async fn game_state_must_be_the_pda_for_the_table_id() {
+    let mut table = TestTable::new(3).await;
+    let game_state = table.game_state;
+
+    assert_poker_error(
+        table
+            .initialize_table(3, TABLE_ID + 1, game_state, table_config())
+            .await,
+        PokerError::InvalidGameAccount,
+    );
+
+    // Another table id gets its own account and vault
+    let (other_table, _) = Pubkey::find_program_address(
+        &[GAME_SEED, table.authority().as_ref(), &(TABLE_ID + 1).to_le_bytes()],
+        &table.program_id,
+    );
+    table
+        .initialize_table(3, TABLE_ID + 1, other_table, table_config())
+        .await
+        .unwrap();
+
+    for max_players in [0, 1, MAX_PLAYERS + 1] {
+        assert_poker_error(
+            table.initialize(max_players).await,
+            PokerError::InvalidMaxPlayers,
+        );
+    }
+}
+
+#[tokio::test]
+async fn full_table_fits_its_account() {
+    let players = MAX_PLAYERS as usize;
+    let mut table = TestTable::seated(players).await;
+
+    // Grow every field of the seated table to its largest
+    let mut state = table.game_state().await;
+    for player in state.players.iter_mut().flatten() {
+        player.name = "n".repeat(MAX_NAME_LEN);
+        player.seed_commitment = Some([1; 32]);
+        player.revealed_seed = Some([2; 32]);
+        player.hole_index = Some(0);
+        player.key_commitment = Some([3; 32]);
+        player.decryption_key = Some([4; 32]);
+        player.hole_cards = Some([5, 6]);
+        player.board_unmasks = vec![[10; 32]; 5];
+    }
+    state.pots = vec![
+        SidePot {
+            amount: 1,
+            eligible_seats: (0..MAX_PLAYERS).collect(),
+        };
+        players
+    ];
+    state.deck = vec![[7; 32]; 52];
+    state.encrypted_deck = vec![[8; 32]; 2 * players + 5];
+    state.board = vec![9; 5];
+    state.randomness_oracle = Some(Pubkey::new_unique());
+    state.config.ante = Some(1);
+    state.small_blind_seat = Some(0);
+    state.big_blind_seat = Some(1);
+
+    assert_eq!(
+        state.try_to_vec().unwrap().len(),
+        GameState::space(MAX_PLAYERS)
+    );
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::{assert_poker_error, TestTable, BUY_IN};
+use solana_poker::{GameStage, PokerError};
+
+/// Three players, seat 2 bought in for `seat_2_buy_in`, with the preflop
+/// action checked through to the flop
+async fn table_on_the_flop(seat_2_buy_in: u64) -> TestTable {
+    let mut table = TestTable::new(3).await;
+    table.initialize(3).await.unwrap();
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+    table.buy_in(2, seat_2_buy_in).await.unwrap();
+
+    table.start_round().await.unwrap();
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+    table
+}
+
+#[tokio::test]
+async fn a_re_raise_must_match_the_last_raise() {
+    let mut table = table_on_the_flop(BUY_IN).await;
+    let big_blind = table.game_state().await.config.big_blind;
+
+    table.bet(1, big_blind).await.unwrap();
+    table.raise(2, 3 * big_blind).await.unwrap();
+    assert_eq!(table.game_state().await.min_raise(), 3 * big_blind);
+
+    assert_poker_error(table.raise(0, 2 * big_blind).await, PokerError::RaiseTooSmall);
+    table.raise(0, 3 * big_blind).await.unwrap();
+    assert_eq!(table.game_state().await.current_bet, 7 * big_blind);
+
+    // The minimum starts over at the big blind on the next street
+    table.call(1).await.unwrap();
+    table.call(2).await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Turn);
+    assert_eq!(state.min_raise(), big_blind);
+}
+
+#[tokio::test]
+async fn a_short_all_in_does_not_reopen_the_betting() {
+    // Seat 2 has 9 big blinds behind on the flop
+    let mut table = table_on_the_flop(BUY_IN / 2).await;
+    let big_blind = table.game_state().await.config.big_blind;
+
+    // Seat 2's all-in raises seat 1's bet by only one big blind
+    table.bet(1, 8 * big_blind).await.unwrap();
+    table.all_in(2).await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.current_bet, 9 * big_blind);
+    assert_eq!(state.min_raise(), 8 * big_blind);
+
+    // Seat 0 has yet to act and may still raise, but chooses to call
+    table.call(0).await.unwrap();
+
+    // Seat 1 already acted, so can only call or fold
+    assert_poker_error(table.raise(1, 8 * big_blind).await, PokerError::BettingNotReopened);
+    assert_poker_error(table.all_in(1).await, PokerError::BettingNotReopened);
+    table.call(1).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Turn);
+}
+
+#[tokio::test]
+async fn an_oversized_raise_is_rejected_without_overflowing() {
+    let mut table = table_on_the_flop(BUY_IN).await;
+    let big_blind = table.game_state().await.config.big_blind;
+
+    table.bet(1, big_blind).await.unwrap();
+    assert_poker_error(table.raise(2, u64::MAX).await, PokerError::InsufficientChips);
+    table.raise(2, big_blind).await.unwrap();
+}
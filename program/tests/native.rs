@@ -0,0 +1,112 @@
+mod common;
+
+use common::{
+    assert_instruction_error, assert_poker_error, buy_in_instruction, table_config, TestTable,
+    BUY_IN,
+};
+use solana_poker::{custody::Custody, PokerError, PokerInstruction};
+use solana_program::instruction::{AccountMeta, InstructionError};
+
+#[tokio::test]
+async fn native_tables_escrow_lamports() {
+    let mut table = TestTable::native(2).await;
+    table.initialize(2).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.custody, Custody::Lamports);
+    assert_eq!(state.pot_token_account, state.pot_authority);
+    let escrow = table
+        .context
+        .banks_client
+        .get_account(table.pot)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(escrow.owner, table.program_id);
+    let reserve = escrow.lamports;
+
+    let wallets = [table.players[0].token_account, table.players[1].token_account];
+    let starting = table.lamports(wallets[0]).await + table.lamports(wallets[1]).await;
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+    let pot = table.pot;
+    assert_eq!(table.lamports(pot).await, reserve + 2 * BUY_IN);
+
+    table.start_round().await.unwrap();
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // Winnings come out of the escrow, which keeps only its rent reserve
+    table.cash_out(0).await.unwrap();
+    table.cash_out(1).await.unwrap();
+    assert_eq!(table.lamports(pot).await, reserve);
+    assert_eq!(
+        table.lamports(wallets[0]).await + table.lamports(wallets[1]).await,
+        starting
+    );
+}
+
+#[tokio::test]
+async fn native_tables_move_lamports_only_from_the_players_wallet() {
+    let mut table = TestTable::native(2).await;
+    assert_poker_error(
+        table.initialize_with(2, table_config()).await,
+        PokerError::InvalidTableConfig,
+    );
+    table.initialize(2).await.unwrap();
+
+    // Paying from someone else's wallet
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[2] = AccountMeta::new(table.players[1].token_account, false);
+    assert_poker_error(
+        table
+            .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+            .await,
+        PokerError::InvalidTokenAccount,
+    );
+
+    // A token program on a native table
+    let mut accounts = table.buy_in_accounts(0);
+    accounts[5] = AccountMeta::new_readonly(spl_token::id(), false);
+    assert_instruction_error(
+        table
+            .send_instruction(buy_in_instruction(BUY_IN), accounts, Some(0))
+            .await,
+        InstructionError::IncorrectProgramId,
+    );
+
+    table.buy_in(0, BUY_IN).await.unwrap();
+
+    // Cashing out into someone else's wallet
+    let mut accounts = table.cash_out_accounts(0);
+    accounts[3] = AccountMeta::new(table.players[1].token_account, false);
+    assert_poker_error(
+        table
+            .send_instruction(PokerInstruction::CashOut, accounts, Some(0))
+            .await,
+        PokerError::InvalidTokenAccount,
+    );
+    table.cash_out(0).await.unwrap();
+}
+
+#[tokio::test]
+async fn lamports_sent_to_the_escrow_do_not_lock_the_table() {
+    let mut table = TestTable::native(2).await;
+
+    // Funding the escrow address before the table exists does not block it
+    let pot = table.pot;
+    table.send_lamports(pot, 1_000_000).await;
+    table.initialize(2).await.unwrap();
+    assert_eq!(table.game_state().await.custody, Custody::Lamports);
+
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+
+    // Nor does a lamport sent to it mid-session, which backs no chips
+    table.send_lamports(pot, 1).await;
+    let before = table.lamports(pot).await;
+    table.cash_out(0).await.unwrap();
+    table.cash_out(1).await.unwrap();
+    assert_eq!(table.lamports(pot).await, before - 2 * BUY_IN);
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::{assert_poker_error, cards, TestTable, BUY_IN};
+use solana_poker::{randomness::MAX_RANDOMNESS_AGE_SLOTS, shuffle, sra, GameStage, PokerError};
+
+const RANDOMNESS: [u8; 32] = [42; 32];
+
+async fn oracle_table() -> TestTable {
+    let mut table = TestTable::seated(3).await;
+    let oracle = table.oracle;
+    table.set_randomness_oracle(Some(oracle)).await.unwrap();
+    table
+}
+
+#[tokio::test]
+async fn oracle_randomness_seeds_the_deck_without_player_seeds() {
+    let mut table = oracle_table().await;
+
+    assert_poker_error(
+        table.commit_seed(0, [1; 32]).await,
+        PokerError::PlayerSeedsDisabled,
+    );
+
+    let randomness = table.randomness;
+    table.fulfill_randomness(RANDOMNESS).await.unwrap();
+    table.start_round_from(randomness).await.unwrap();
+
+    // Everyone with chips is dealt in and the deck starts from the oracle's seed
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::PreFlop);
+    assert_eq!(state.dealt_in(), 3);
+    let deck: Vec<u8> = state.deck.iter().map(|&card| sra::decode(card).unwrap()).collect();
+    assert_eq!(deck, shuffle::deck_from_seed(&RANDOMNESS));
+
+    // The hand plays out as usual
+    table.deal().await.unwrap();
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.chips(2).await, BUY_IN + state.config.small_blind);
+
+    // The same result cannot seed a second hand
+    assert_poker_error(
+        table.start_round_from(randomness).await,
+        PokerError::StaleRandomness,
+    );
+    table.context.warp_to_slot(100).unwrap();
+    table.fulfill_randomness([43; 32]).await.unwrap();
+    table.start_round_from(randomness).await.unwrap();
+}
+
+#[tokio::test]
+async fn randomness_must_be_fulfilled_fresh_and_from_the_oracle() {
+    let mut table = oracle_table().await;
+    let randomness = table.randomness;
+
+    assert_poker_error(
+        table.start_round_from(randomness).await,
+        PokerError::RandomnessNotFulfilled,
+    );
+
+    // Any other account, however it is filled in, is not the oracle's
+    let game_state = table.game_state;
+    assert_poker_error(
+        table.start_round_from(game_state).await,
+        PokerError::InvalidRandomnessAccount,
+    );
+
+    table.fulfill_randomness(RANDOMNESS).await.unwrap();
+    let slot = table.context.banks_client.get_root_slot().await.unwrap();
+    table
+        .context
+        .warp_to_slot(slot + MAX_RANDOMNESS_AGE_SLOTS + 10)
+        .unwrap();
+    assert_poker_error(
+        table.start_round_from(randomness).await,
+        PokerError::StaleRandomness,
+    );
+}
+
+#[tokio::test]
+async fn tables_can_switch_back_to_player_seeds() {
+    let mut table = oracle_table().await;
+    table.set_randomness_oracle(None).await.unwrap();
+
+    table
+        .start_round_with(&cards("2h 7d Kh Kd As Ad 3c 8h Jd 4s 5c"))
+        .await
+        .unwrap();
+    assert_eq!(table.hole_cards(2).await.to_vec(), cards("As Ad"));
+
+    // The oracle can only be changed between hands
+    let oracle = table.oracle;
+    assert_poker_error(
+        table.set_randomness_oracle(Some(oracle)).await,
+        PokerError::HandInProgress,
+    );
+}
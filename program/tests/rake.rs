@@ -0,0 +1,92 @@
+mod common;
+
+use common::{assert_poker_error, table_config, TestTable, BUY_IN};
+use solana_poker::{GameStage, PokerError, PokerInstruction, TableConfig};
+use solana_program::instruction::AccountMeta;
+use solana_sdk::signature::Signer;
+
+/// 5% rake capped at a big blind, none on hands that end preflop
+fn raked_config() -> TableConfig {
+    TableConfig {
+        rake_bps: 500,
+        rake_cap_bbs: 1,
+        no_flop_no_drop: true,
+        ..table_config()
+    }
+}
+
+async fn raked_table() -> TestTable {
+    let mut table = TestTable::new(2).await;
+    table.initialize_with(2, raked_config()).await.unwrap();
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+    table
+}
+
+async fn total_chips(table: &mut TestTable) -> u64 {
+    table.chips(0).await + table.chips(1).await
+}
+
+#[tokio::test]
+async fn rake_skips_preflop_hands_and_uncalled_bets() {
+    let mut table = raked_table().await;
+
+    // No flop, no drop
+    table.start_round().await.unwrap();
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.game_state().await.rake_accrued, 0);
+    assert_eq!(total_chips(&mut table).await, 2 * BUY_IN);
+
+    // Only the called big blinds are raked, not the bet that took the pot
+    table.start_round().await.unwrap();
+    table.check_or_call_current().await.unwrap();
+    table.check_or_call_current().await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Flop);
+    let seat = table.current_turn().await;
+    table.bet(seat, 200_000).await.unwrap();
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+    table.showdown().await.unwrap();
+
+    let rake = 100_000 * 5 / 100;
+    assert_eq!(table.game_state().await.rake_accrued, rake);
+    assert_eq!(total_chips(&mut table).await, 2 * BUY_IN - rake);
+}
+
+#[tokio::test]
+async fn rake_is_capped_and_withdrawn_to_the_treasury() {
+    let mut table = raked_table().await;
+    table.start_round().await.unwrap();
+    let seat = table.current_turn().await;
+    table.all_in(seat).await.unwrap();
+    let seat = table.current_turn().await;
+    table.call(seat).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // 5% of the 2 BUY_IN pot is well over the one big blind cap
+    let big_blind = table_config().big_blind;
+    assert_eq!(table.game_state().await.rake_accrued, big_blind);
+    assert_eq!(total_chips(&mut table).await, 2 * BUY_IN - big_blind);
+
+    // Only the authority withdraws, into a token account for the table's mint
+    let (mint, authority) = (table.mint, table.authority());
+    let treasury = table.add_token_account(mint, authority, 0);
+    let mut accounts = table.withdraw_rake_accounts(treasury);
+    accounts[0] = AccountMeta::new_readonly(table.players[0].keypair.pubkey(), true);
+    assert_poker_error(
+        table
+            .send_instruction(PokerInstruction::WithdrawRake, accounts, Some(0))
+            .await,
+        PokerError::Unauthorized,
+    );
+
+    table.withdraw_rake(treasury).await.unwrap();
+    assert_eq!(table.token_balance(treasury).await, big_blind);
+    assert_eq!(table.game_state().await.rake_accrued, 0);
+    let pot = table.pot;
+    assert_eq!(table.token_balance(pot).await, 2 * BUY_IN - big_blind);
+
+    assert_poker_error(table.withdraw_rake(treasury).await, PokerError::NoRakeAccrued);
+}
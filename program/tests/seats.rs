@@ -0,0 +1,104 @@
+mod common;
+
+use common::{assert_poker_error, table_config, TestTable, BUY_IN};
+use solana_poker::PokerError;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn players_choose_a_seat_and_keep_it() {
+    let mut table = TestTable::new(3).await;
+    table.initialize(4).await.unwrap();
+
+    table.buy_in_at(0, 2, BUY_IN).await.unwrap();
+    assert_poker_error(table.buy_in_at(1, 2, BUY_IN).await, PokerError::SeatTaken);
+    assert_poker_error(table.buy_in_at(1, 4, BUY_IN).await, PokerError::InvalidSeat);
+
+    // Without a choice the first empty seat is taken
+    table.buy_in(1, BUY_IN).await.unwrap();
+    table.buy_in_at(2, 3, BUY_IN).await.unwrap();
+
+    // Topping up happens in the seat a player already has
+    assert_poker_error(table.buy_in_at(0, 1, BUY_IN).await, PokerError::InvalidSeat);
+    table.buy_in_at(0, 2, BUY_IN).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.players.len(), 4);
+    assert_eq!(state.current_players, 3);
+    assert!(state.seat(1).is_none());
+    for (player, seat) in [(0, 2), (1, 0), (2, 3)] {
+        let seated = state.seat(seat).unwrap();
+        assert_eq!(seated.pubkey, table.players[player].keypair.pubkey());
+        assert_eq!(seated.seat_index as usize, seat);
+    }
+    assert_eq!(state.seat(2).unwrap().chips, 2 * BUY_IN);
+}
+
+#[tokio::test]
+async fn cashing_out_leaves_every_other_seat_alone() {
+    let mut table = TestTable::seated(3).await;
+    let config = table_config();
+
+    // Everyone folds to the big blind in seat 2; the button moves to seat 1
+    table.start_round().await.unwrap();
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.game_state().await.dealer_index, 1);
+
+    // The player on the button leaves
+    table.cash_out(1).await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(1).is_none());
+    assert_eq!(state.seat(2).unwrap().seat_index, 2);
+    assert_eq!(state.current_players, 2);
+
+    // Blinds and the action skip the empty seat: the button moves on to
+    // seat 2, which posts the small blind heads-up
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.seat(2).unwrap().current_bet, config.small_blind);
+    assert_eq!(state.seat(0).unwrap().current_bet, config.big_blind);
+    assert_eq!(state.current_player_turn, 2);
+
+    // The button moves past the empty seat
+    table.fold(2).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.game_state().await.dealer_index, 0);
+
+    // The empty seat is free for the next player to sit down in
+    table.buy_in(1, BUY_IN).await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.seat(1).unwrap().pubkey, table.players[1].keypair.pubkey());
+}
+
+#[tokio::test]
+async fn players_joining_mid_hand_wait_for_the_next_deal() {
+    let mut table = TestTable::new(3).await;
+    table.initialize(3).await.unwrap();
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+    table.start_round().await.unwrap();
+
+    // Seat 2 sits down with the hand under way and is passed over
+    table.buy_in(2, BUY_IN).await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(2).unwrap().has_folded);
+    assert_eq!(state.current_player_turn, 0);
+    assert_poker_error(table.call(2).await, PokerError::NotDealtIn);
+
+    // Nobody tops up a stack that may be in the pot
+    assert_poker_error(table.buy_in(0, BUY_IN).await, PokerError::HandInProgress);
+
+    table.call(0).await.unwrap();
+    table.check(1).await.unwrap();
+    assert_eq!(table.game_state().await.current_player_turn, 1);
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.chips(2).await, BUY_IN);
+
+    // The next hand deals them in
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(2).unwrap().hole_index.is_some());
+    assert!(!state.seat(2).unwrap().has_folded);
+}
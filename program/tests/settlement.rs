@@ -0,0 +1,129 @@
+mod common;
+
+use common::{assert_poker_error, cards, TestTable, BUY_IN, STARTING_BALANCE};
+use solana_poker::{GameStage, PokerError};
+
+#[tokio::test]
+async fn end_round_pays_only_the_hand_pot() {
+    let mut table = TestTable::seated(3).await;
+
+    // Dealer is seat 0, so seat 1 posts the small blind and seat 2 the big blind
+    table.start_round().await.unwrap();
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.pot_total, 0);
+    assert_eq!(state.seat(0).unwrap().chips, BUY_IN);
+    assert_eq!(state.seat(1).unwrap().chips, BUY_IN - state.config.small_blind);
+    assert_eq!(state.seat(2).unwrap().chips, BUY_IN + state.config.small_blind);
+
+    // The vault still backs every stack
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn third_player_stack_survives_another_players_win() {
+    let mut table = TestTable::seated(3).await;
+
+    // Seat 0 sits the hand out while the blinds play it down; seat 1 holds aces
+    table
+        .start_round_with(&cards("2h 7d As Ad Kc 9s 3c 8h Jd 4s 5c"))
+        .await
+        .unwrap();
+    let big_blind = table.game_state().await.config.big_blind;
+    table.fold(0).await.unwrap();
+    table.call(1).await.unwrap();
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+    table.showdown().await.unwrap();
+
+    assert_eq!(table.chips(0).await, BUY_IN);
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind);
+    assert_eq!(table.chips(2).await, BUY_IN - big_blind);
+
+    // The uninvolved player can still withdraw their whole buy-in
+    table.cash_out(0).await.unwrap();
+    let player_0_account = table.players[0].token_account;
+    assert_eq!(table.token_balance(player_0_account).await, STARTING_BALANCE);
+    assert_eq!(table.token_balance(table.pot).await, 2 * BUY_IN);
+}
+
+#[tokio::test]
+async fn chopped_pot_gives_the_odd_chip_left_of_the_button() {
+    // A royal flush on the board plays for everyone
+    let mut table = TestTable::seated(3).await;
+    table
+        .start_round_with(&cards("2c 3d 4c 5d 6c 7d As Ks Qs Js 10s"))
+        .await
+        .unwrap();
+
+    // Preflop: seat 0 raises to 100_001 and both blinds call
+    table.raise(0, 50_001).await.unwrap();
+    table.call(1).await.unwrap();
+    table.call(2).await.unwrap();
+
+    // Flop: seat 1 bets, seat 2 calls, seat 0 folds
+    table.bet(1, 50_000).await.unwrap();
+    table.call(2).await.unwrap();
+    table.fold(0).await.unwrap();
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+    assert_eq!(table.game_state().await.pot_total, 400_003);
+
+    // Seat 1 is first left of the button (seat 0) and gets the odd chip
+    table.reveal_keys().await.unwrap();
+    let logs = table.end_round_logs().await;
+    assert!(logs.iter().any(|log| log.contains("(seat 1) won 0.200002.")));
+    assert!(logs.iter().any(|log| log.contains("(seat 2) won 0.200001.")));
+
+    assert_eq!(table.chips(0).await, BUY_IN - 100_001);
+    assert_eq!(table.chips(1).await, BUY_IN + 50_001);
+    assert_eq!(table.chips(2).await, BUY_IN + 50_000);
+}
+
+#[tokio::test]
+async fn showdown_waits_for_every_key() {
+    let mut table = TestTable::seated(3).await;
+    table
+        .start_round_with(&cards("2h 7d As Ad Kc Kd 3c 8h Jd 4s 5c"))
+        .await
+        .unwrap();
+    table.fold(0).await.unwrap();
+    table.call(1).await.unwrap();
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+
+    // Seat 0 folded, but its layer is on every card and its key is needed too
+    for seat in 1..3 {
+        let (encryption_key, decryption_key) = (
+            table.players[seat].encryption_key,
+            table.players[seat].decryption_key,
+        );
+        table
+            .reveal_key(seat, encryption_key, decryption_key)
+            .await
+            .unwrap();
+    }
+    assert_poker_error(table.end_round().await, PokerError::KeysNotRevealed);
+
+    // Another player's key does not match seat 0's commitment
+    let (encryption_key, decryption_key) = (
+        table.players[1].encryption_key,
+        table.players[1].decryption_key,
+    );
+    assert_poker_error(
+        table.reveal_key(0, encryption_key, decryption_key).await,
+        PokerError::InvalidKeyReveal,
+    );
+
+    table.reveal_keys().await.unwrap();
+    table.end_round().await.unwrap();
+
+    assert_eq!(table.chips(1).await, BUY_IN + table.game_state().await.config.big_blind);
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use common::{assert_poker_error, seed_for, TestTable, BUY_IN};
+use solana_poker::{shuffle, sra, GameStage, PokerError, SeatStatus};
+use solana_sdk::signature::Signer;
+
+const PHASE: i64 = 120;
+
+fn commitment_for(table: &TestTable, player: usize) -> [u8; 32] {
+    shuffle::commitment(&table.players[player].keypair.pubkey(), &seed_for(player))
+}
+
+#[tokio::test]
+async fn revealed_seeds_shuffle_a_full_deck() {
+    let mut table = TestTable::seated(3).await;
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::PreFlop);
+
+    // The seeded deck goes into the encryption passes in the clear
+    let deck: Vec<u8> = state.deck.iter().map(|&card| sra::decode(card).unwrap()).collect();
+    let mut sorted = deck.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, (0..52).collect::<Vec<u8>>());
+
+    // Every revealed seed feeds the deck, in seat order
+    let expected = shuffle::deck_from_seed(&shuffle::combine_seeds(
+        [seed_for(0), seed_for(1), seed_for(2)].iter(),
+    ));
+    assert_eq!(deck, expected);
+
+    // Settling the hand clears the shuffle for the next one
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.deck.is_empty());
+    assert!(state.seated().all(|p| p.seed_commitment.is_none()));
+}
+
+#[tokio::test]
+async fn reveals_wait_for_every_commitment() {
+    let mut table = TestTable::seated(3).await;
+    let commitment = commitment_for(&table, 0);
+    table.commit_seed(0, commitment).await.unwrap();
+
+    assert_poker_error(
+        table.commit_seed(0, commitment).await,
+        PokerError::SeedAlreadyCommitted,
+    );
+    assert_poker_error(
+        table.reveal_seed(0, seed_for(0)).await,
+        PokerError::RevealPhaseNotOpen,
+    );
+    assert_poker_error(table.start_round_only().await, PokerError::ShuffleIncomplete);
+
+    for player in 1..3 {
+        let commitment = commitment_for(&table, player);
+        table.commit_seed(player, commitment).await.unwrap();
+    }
+
+    // A seed that does not match the commitment is rejected
+    assert_poker_error(
+        table.reveal_seed(0, seed_for(1)).await,
+        PokerError::InvalidSeedReveal,
+    );
+    table.reveal_seed(0, seed_for(0)).await.unwrap();
+
+    // Commitments close once reveals open
+    assert_poker_error(
+        table.commit_seed(1, commitment_for(&table, 1)).await,
+        PokerError::CommitPhaseClosed,
+    );
+    assert_poker_error(table.start_round_only().await, PokerError::ShuffleIncomplete);
+}
+
+#[tokio::test]
+async fn withheld_reveal_forfeits_a_big_blind_after_the_deadline() {
+    let mut table = TestTable::seated(3).await;
+    for player in 0..3 {
+        let commitment = commitment_for(&table, player);
+        table.commit_seed(player, commitment).await.unwrap();
+    }
+    table.reveal_seed(1, seed_for(1)).await.unwrap();
+    table.reveal_seed(2, seed_for(2)).await.unwrap();
+
+    assert_poker_error(table.start_round_only().await, PokerError::ShuffleIncomplete);
+
+    table.warp_clock(2 * PHASE + 1).await;
+    assert_poker_error(
+        table.reveal_seed(0, seed_for(0)).await,
+        PokerError::RevealPhaseNotOpen,
+    );
+    table.start_round_only().await.unwrap();
+
+    // Seat 0 is not dealt in and its big blind is dead money in the pot
+    let state = table.game_state().await;
+    let big_blind = state.config.big_blind;
+    assert!(state.seat(0).unwrap().has_folded);
+    assert_eq!(state.seat(0).unwrap().chips, BUY_IN - big_blind);
+    assert_eq!(state.pot_total, big_blind + state.config.small_blind + big_blind);
+
+    // Heads up between the two revealers; seat 2 picks up the dead blind
+    let small_blind = state.config.small_blind;
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.chips(2).await, BUY_IN + small_blind + big_blind);
+
+    // Seat 0 is sat out, so the next hand deals without waiting on them
+    let state = table.game_state().await;
+    assert_eq!(state.seat(0).unwrap().status, SeatStatus::SittingOut);
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::PreFlop);
+    assert_eq!(state.seat(0).unwrap().hole_index, None);
+}
+
+#[tokio::test]
+async fn too_few_reveals_restart_the_shuffle() {
+    // Seat 0 reveals, seat 1 commits and withholds, seat 2 never commits
+    let mut table = TestTable::seated(3).await;
+    for player in 0..2 {
+        let commitment = commitment_for(&table, player);
+        table.commit_seed(player, commitment).await.unwrap();
+    }
+
+    // Reveals open once the commit window closes without seat 2
+    table.warp_clock(PHASE + 1).await;
+    table.reveal_seed(0, seed_for(0)).await.unwrap();
+    table.warp_clock(PHASE).await;
+    table.start_round_only().await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.commit_deadline, 0);
+    assert!(state.seated().all(|p| p.seed_commitment.is_none()));
+
+    // Both are sat out and the withheld reveal's big blind goes to seat 0
+    let big_blind = state.config.big_blind;
+    assert_eq!(state.seat(0).unwrap().status, SeatStatus::Playing);
+    assert_eq!(state.seat(1).unwrap().status, SeatStatus::SittingOut);
+    assert_eq!(state.seat(2).unwrap().status, SeatStatus::SittingOut);
+    assert_eq!(table.chips(0).await, BUY_IN + big_blind);
+    assert_eq!(table.chips(1).await, BUY_IN - big_blind);
+    assert_eq!(table.chips(2).await, BUY_IN);
+
+    // A fresh shuffle deals in whoever is back
+    table.sit_in(1).await.unwrap();
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::PreFlop);
+    assert_eq!(state.dealt_in(), 2);
+}
+
+#[tokio::test]
+async fn withheld_reveal_penalty_is_not_refunded_when_the_hand_is_called_off() {
+    let mut table = TestTable::seated(3).await;
+    for player in 0..3 {
+        let commitment = commitment_for(&table, player);
+        table.commit_seed(player, commitment).await.unwrap();
+    }
+    table.reveal_seed(1, seed_for(1)).await.unwrap();
+    table.reveal_seed(2, seed_for(2)).await.unwrap();
+    table.warp_clock(2 * PHASE + 1).await;
+    table.start_round_only().await.unwrap();
+
+    // Seat 1 has the button and small blind heads-up and never encrypts
+    let state = table.game_state().await;
+    let (small_blind, big_blind) = (state.config.small_blind, state.config.big_blind);
+    table.warp_clock(61).await;
+    table.timeout_action(2, false).await.unwrap();
+
+    // Seat 0's dead big blind stays forfeited, along with seat 1's blind
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(table.chips(0).await, BUY_IN - big_blind);
+    assert_eq!(table.chips(1).await, BUY_IN - small_blind);
+    assert_eq!(table.chips(2).await, BUY_IN + small_blind + big_blind);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
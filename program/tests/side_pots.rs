@@ -0,0 +1,119 @@
+mod common;
+
+use common::{assert_poker_error, cards, TestTable};
+use solana_poker::{GameStage, PokerError, SidePot};
+
+async fn table_with_stacks(stacks: &[u64]) -> TestTable {
+    let mut table = TestTable::new(stacks.len()).await;
+    table.initialize(stacks.len() as u8).await.unwrap();
+    for (seat, &stack) in stacks.iter().enumerate() {
+        table.buy_in(seat, stack).await.unwrap();
+    }
+    table
+}
+
+#[tokio::test]
+async fn all_ins_build_main_and_side_pots() {
+    // Seat 1 makes three aces; seat 0 has the second best hand
+    let mut table = table_with_stacks(&[500_000, 1_000_000, 2_000_000]).await;
+    table
+        .start_round_with(&cards("Kh Kd As Ad 7c 2s Ac Kc 9d 4h 3s"))
+        .await
+        .unwrap();
+
+    table.all_in(0).await.unwrap();
+    table.all_in(1).await.unwrap();
+    table.call(2).await.unwrap();
+
+    // Nobody is left to bet, so the board runs out to showdown
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Showdown);
+    assert_eq!(state.pot_total, 2_500_000);
+    assert_eq!(
+        state.pots,
+        vec![
+            SidePot {
+                amount: 1_500_000,
+                eligible_seats: vec![0, 1, 2],
+            },
+            SidePot {
+                amount: 1_000_000,
+                eligible_seats: vec![1, 2],
+            },
+        ]
+    );
+
+    table.showdown().await.unwrap();
+
+    assert_eq!(table.chips(0).await, 0);
+    assert_eq!(table.chips(1).await, 2_500_000);
+    assert_eq!(table.chips(2).await, 1_000_000);
+
+    // The busted seat is not dealt into the next hand
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(0).unwrap().has_folded);
+    assert_eq!(state.seat(0).unwrap().total_contribution, 0);
+    assert_eq!(state.pot_total, state.config.small_blind + state.config.big_blind);
+}
+
+#[tokio::test]
+async fn calling_short_goes_all_in_and_returns_the_uncalled_excess() {
+    // Seat 0 holds the best hand but only matched part of seat 1's all-in
+    let mut table = table_with_stacks(&[500_000, 1_000_000, 1_000_000]).await;
+    table
+        .start_round_with(&cards("As Ad Kh Kd 7c 2s Ac 8c 9d 4h 3s"))
+        .await
+        .unwrap();
+
+    table.call(0).await.unwrap();
+    table.all_in(1).await.unwrap();
+    table.fold(2).await.unwrap();
+
+    // Seat 0 only has 450_000 behind but can still call
+    table.call(0).await.unwrap();
+
+    let state = table.game_state().await;
+    assert!(state.seat(0).unwrap().is_all_in);
+    assert_eq!(state.game_stage, GameStage::Showdown);
+    assert_eq!(
+        state.pots,
+        vec![
+            SidePot {
+                amount: 1_050_000,
+                eligible_seats: vec![0, 1],
+            },
+            SidePot {
+                amount: 500_000,
+                eligible_seats: vec![1],
+            },
+        ]
+    );
+
+    // Seat 0 wins the main pot; the uncalled side pot goes back to seat 1
+    table.showdown().await.unwrap();
+
+    assert_eq!(table.chips(0).await, 1_050_000);
+    assert_eq!(table.chips(1).await, 500_000);
+    assert_eq!(table.chips(2).await, 950_000);
+}
+
+#[tokio::test]
+async fn all_in_players_are_skipped_for_the_rest_of_the_hand() {
+    let mut table = table_with_stacks(&[500_000, 1_000_000, 1_000_000]).await;
+    table.start_round().await.unwrap();
+
+    table.all_in(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.call(2).await.unwrap();
+
+    // Seats 1 and 2 keep betting on the flop around the all-in seat 0
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.current_player_turn, 1);
+    assert_poker_error(table.check(0).await, PokerError::PlayerAllIn);
+
+    table.check(1).await.unwrap();
+    table.check(2).await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Turn);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{assert_poker_error, cards, TestTable, BUY_IN};
+use solana_poker::{PokerError, SeatStatus};
+
+#[tokio::test]
+async fn sitting_out_keeps_the_seat_but_skips_the_deal() {
+    let mut table = TestTable::seated(3).await;
+
+    table.sit_out(1).await.unwrap();
+    assert_poker_error(table.sit_out(1).await, PokerError::InvalidSeatStatus);
+
+    // Seat 1 is skipped, so heads-up the button posts the small blind and
+    // seat 2 the big blind, passing seat 1 by on the way
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    let seat = state.seat(1).unwrap();
+    assert_eq!(seat.status, SeatStatus::SittingOut);
+    assert_eq!(seat.hole_index, None);
+    assert!(seat.missed_big_blind);
+    assert_eq!(state.seat(0).unwrap().current_bet, state.config.small_blind);
+    assert_eq!(state.seat(2).unwrap().current_bet, state.config.big_blind);
+
+    table.fold(0).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.chips(1).await, BUY_IN);
+
+    table.sit_in(1).await.unwrap();
+    assert_poker_error(table.sit_in(1).await, PokerError::InvalidSeatStatus);
+    assert_eq!(table.game_state().await.seat(1).unwrap().status, SeatStatus::Playing);
+}
+
+#[tokio::test]
+async fn leaving_players_finish_their_hand_first() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    // The big blind decides to leave but still wins the hand
+    table.leave_after_hand(2).await.unwrap();
+    assert_poker_error(table.leave_after_hand(2).await, PokerError::InvalidSeatStatus);
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    let state = table.game_state().await;
+    let seat = state.seat(2).unwrap();
+    assert_eq!(seat.status, SeatStatus::PendingLeave);
+    assert_eq!(seat.chips, BUY_IN + state.config.small_blind);
+
+    // Their chips keep the seat until they cash out
+    table.cash_out(2).await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(2).is_none());
+    assert_eq!(state.current_players, 2);
+}
+
+#[tokio::test]
+async fn busted_players_buy_in_or_leave() {
+    // Seat 1 holds aces against seat 0's seven high
+    let mut table = TestTable::seated(2).await;
+    table
+        .start_round_with(&cards("2c 7d As Ad Kh 9s 4h 3d Jc"))
+        .await
+        .unwrap();
+    table.all_in(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.seat(0).unwrap().chips, 0);
+    assert_eq!(state.seat(0).unwrap().status, SeatStatus::Busted);
+    assert_poker_error(table.sit_in(0).await, PokerError::InvalidSeatStatus);
+
+    // With nothing to cash out, leaving frees the seat straight away
+    table.leave_after_hand(0).await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(0).is_none());
+    assert_eq!(state.current_players, 1);
+}
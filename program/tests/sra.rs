@@ -0,0 +1,90 @@
+use curve25519_dalek::{constants::BASEPOINT_ORDER, ristretto::RistrettoPoint, scalar::Scalar};
+use solana_poker::hand_eval::DECK_SIZE;
+use solana_poker::sra::{
+    combine_keys, decode, decrypt, decryption_key, encode, encrypt, is_element, is_key_pair,
+    key_from_bytes, Key, CARD_DOMAIN,
+};
+use solana_program::hash::hashv;
+
+fn key(byte: u8) -> (Key, Key) {
+    let encryption_key = key_from_bytes(&[byte; 64]);
+    (encryption_key, decryption_key(encryption_key).unwrap())
+}
+
+#[test]
+fn card_points_are_hashed_onto_the_curve() {
+    for card in 0..DECK_SIZE {
+        let mut uniform = [0; 64];
+        uniform[..32].copy_from_slice(&hashv(&[CARD_DOMAIN, &[card], &[0]]).to_bytes());
+        uniform[32..].copy_from_slice(&hashv(&[CARD_DOMAIN, &[card], &[1]]).to_bytes());
+        let point = RistrettoPoint::from_uniform_bytes(&uniform).compress().to_bytes();
+
+        assert_eq!(encode(card), point);
+        assert!(is_element(point));
+        assert_eq!(decode(point), Some(card));
+    }
+}
+
+#[test]
+fn key_arithmetic_matches_reference_values() {
+    for byte in [1, 0x5a, 0xff] {
+        let (encryption_key, decryption_key) = key(byte);
+        let reference = Scalar::from_bytes_mod_order_wide(&[byte; 64]);
+        assert_eq!(encryption_key, reference.to_bytes());
+        assert_eq!(decryption_key, reference.invert().to_bytes());
+        assert!(is_key_pair(encryption_key, decryption_key));
+    }
+
+    let keys = [key(2).1, key(3).1, key(4).1];
+    let product = keys
+        .iter()
+        .fold(Scalar::one(), |product, &key| product * Scalar::from_canonical_bytes(key).unwrap());
+    assert_eq!(combine_keys(keys), product.to_bytes());
+}
+
+#[test]
+fn every_card_round_trips_through_a_key() {
+    let (encryption_key, decryption_key) = key(7);
+
+    for card in 0..DECK_SIZE {
+        let encrypted = encrypt(encode(card), encryption_key);
+        assert!(is_element(encrypted));
+        assert_eq!(decode(encrypted), None);
+        assert_eq!(decode(decrypt(encrypted, decryption_key)), Some(card));
+    }
+}
+
+#[test]
+fn layers_come_off_in_any_order() {
+    let keys = [key(11), key(12), key(13)];
+
+    let card = encode(37);
+    let encrypted = keys.iter().fold(card, |value, &(e, _)| encrypt(value, e));
+
+    // Strip the layers in reverse order of how they went on
+    let stripped = keys.iter().rev().fold(encrypted, |value, &(_, d)| decrypt(value, d));
+    assert_eq!(stripped, card);
+
+    // Or all at once with the combined key
+    let combined = combine_keys(keys.iter().map(|&(_, d)| d));
+    assert_eq!(decrypt(encrypted, combined), card);
+}
+
+#[test]
+fn malformed_keys_and_points_are_rejected() {
+    // Zero has no inverse, one encrypts nothing and the group order itself
+    // is not a reduced scalar
+    let mut one = [0; 32];
+    one[0] = 1;
+    for bad_key in [[0; 32], one, BASEPOINT_ORDER.to_bytes()] {
+        assert_eq!(decryption_key(bad_key), None);
+        assert!(!is_key_pair(bad_key, bad_key));
+    }
+    let (encryption_key, _) = key(21);
+    assert!(!is_key_pair(encryption_key, encryption_key));
+
+    // The identity and bytes that are no point on the curve
+    assert!(!is_element([0; 32]));
+    assert!(!is_element([0xff; 32]));
+    assert_eq!(decrypt([0xff; 32], key(21).1), [0; 32]);
+}
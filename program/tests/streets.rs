@@ -0,0 +1,114 @@
+mod common;
+
+use common::{assert_poker_error, TestTable};
+use solana_poker::{GameStage, PokerError};
+
+#[tokio::test]
+async fn big_blind_gets_the_option_before_the_flop() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+    let big_blind = table.game_state().await.config.big_blind;
+
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+
+    // Bets are matched but the big blind has not acted yet
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::PreFlop);
+    assert_eq!(state.current_player_turn, 2);
+
+    table.check(2).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.current_bet, 0);
+    assert!(state.seated().all(|p| p.current_bet == 0));
+    assert_eq!(state.pot_total, 3 * big_blind);
+
+    // First to act postflop is the first seat left of the dealer
+    assert_eq!(state.current_player_turn, 1);
+}
+
+#[tokio::test]
+async fn checking_down_reaches_showdown() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    let mut stages = vec![GameStage::PreFlop];
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        assert_poker_error(table.end_round().await, PokerError::HandNotComplete);
+        table.check_or_call_current().await.unwrap();
+        let stage = table.game_state().await.game_stage;
+        if stages.last() != Some(&stage) {
+            stages.push(stage);
+        }
+    }
+
+    assert_eq!(
+        stages,
+        vec![
+            GameStage::PreFlop,
+            GameStage::Flop,
+            GameStage::Turn,
+            GameStage::River,
+            GameStage::Showdown,
+        ]
+    );
+    assert_poker_error(table.check(1).await, PokerError::NoActiveBettingRound);
+    table.showdown().await.unwrap();
+    assert_eq!(table.game_state().await.game_stage, GameStage::Waiting);
+}
+
+#[tokio::test]
+async fn raise_reopens_the_action() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+    let big_blind = table.game_state().await.config.big_blind;
+
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+
+    // Flop: seat 1 checks, seat 2 bets, seat 0 calls
+    table.check(1).await.unwrap();
+    table.bet(2, big_blind).await.unwrap();
+    table.call(0).await.unwrap();
+
+    // Seat 1 checked before the bet and must respond to it
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.current_player_turn, 1);
+
+    table.call(1).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Turn);
+    assert_eq!(state.pot_total, 6 * big_blind);
+}
+
+#[tokio::test]
+async fn folded_seats_are_skipped_when_a_street_opens() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    table.call(0).await.unwrap();
+    table.fold(1).await.unwrap();
+    table.check(2).await.unwrap();
+
+    // Seat 1 is left of the dealer but folded, so seat 2 opens the flop
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.current_player_turn, 2);
+}
+
+#[tokio::test]
+async fn hand_ends_when_everyone_else_folds() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    table.fold(0).await.unwrap();
+    table.fold(1).await.unwrap();
+
+    assert_eq!(table.game_state().await.game_stage, GameStage::Showdown);
+    assert_poker_error(table.check(2).await, PokerError::NoActiveBettingRound);
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use common::{assert_poker_error, table_config, TestTable};
+use solana_poker::{BettingStructure, PokerError, TableConfig, MAX_RAKE_BPS};
+
+/// 0.01/0.02 with a 0.005 ante and buy-ins from 10 to 50 big blinds
+fn ante_config() -> TableConfig {
+    TableConfig {
+        small_blind: 10_000,
+        big_blind: 20_000,
+        ante: Some(5_000),
+        min_buy_in_bbs: 10,
+        max_buy_in_bbs: 50,
+        ..table_config()
+    }
+}
+
+#[tokio::test]
+async fn insane_configs_are_rejected() {
+    let mut table = TestTable::new(2).await;
+
+    let insane = [
+        TableConfig {
+            small_blind: 0,
+            ..table_config()
+        },
+        TableConfig {
+            big_blind: 20_000,
+            ..table_config()
+        },
+        TableConfig {
+            ante: Some(0),
+            ..table_config()
+        },
+        TableConfig {
+            ante: Some(60_000),
+            ..table_config()
+        },
+        TableConfig {
+            min_buy_in_bbs: 0,
+            ..table_config()
+        },
+        TableConfig {
+            min_buy_in_bbs: 200,
+            ..table_config()
+        },
+        TableConfig {
+            big_blind: u64::MAX,
+            ..table_config()
+        },
+        TableConfig {
+            decimals: 9,
+            ..table_config()
+        },
+        TableConfig {
+            rake_bps: MAX_RAKE_BPS + 1,
+            ..table_config()
+        },
+        TableConfig {
+            betting_structure: BettingStructure::FixedLimit,
+            ..table_config()
+        },
+    ];
+    for config in insane {
+        assert_poker_error(
+            table.initialize_with(2, config).await,
+            PokerError::InvalidTableConfig,
+        );
+    }
+
+    table.initialize_with(2, ante_config()).await.unwrap();
+    assert_eq!(table.game_state().await.config, ante_config());
+}
+
+#[tokio::test]
+async fn buy_ins_are_limited_in_big_blinds() {
+    let mut table = TestTable::new(2).await;
+    table.initialize_with(2, ante_config()).await.unwrap();
+
+    assert_poker_error(table.buy_in(0, 199_999).await, PokerError::InvalidBuyIn);
+    assert_poker_error(table.buy_in(0, 1_000_001).await, PokerError::InvalidBuyIn);
+    table.buy_in(0, 200_000).await.unwrap();
+    table.buy_in(1, 1_000_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn top_ups_are_capped_at_the_maximum_stack() {
+    let mut table = TestTable::new(2).await;
+    table.initialize_with(2, ante_config()).await.unwrap();
+    table.buy_in(0, 900_000).await.unwrap();
+
+    // Below the minimum buy-in is fine for a top-up, up to the maximum stack
+    assert_poker_error(table.buy_in(0, 100_001).await, PokerError::InvalidBuyIn);
+    table.buy_in(0, 100_000).await.unwrap();
+    assert_eq!(table.chips(0).await, 1_000_000);
+
+    // Topping up a full stack in small steps is no way round it
+    assert_poker_error(table.buy_in(0, 1).await, PokerError::InvalidBuyIn);
+    assert_poker_error(table.buy_in(0, u64::MAX).await, PokerError::InvalidBuyIn);
+}
+
+#[tokio::test]
+async fn antes_are_dead_money_in_the_pot() {
+    let mut table = TestTable::new(3).await;
+    table.initialize_with(3, ante_config()).await.unwrap();
+    for player in 0..3 {
+        table.buy_in(player, 500_000).await.unwrap();
+    }
+    table.start_round().await.unwrap();
+
+    // Everyone antes, then the blinds post on top
+    let state = table.game_state().await;
+    assert_eq!(state.pot_total, 3 * 5_000 + 10_000 + 20_000);
+    assert_eq!(state.current_bet, 20_000);
+    assert_eq!(table.chips(0).await, 495_000);
+    assert_eq!(table.chips(1).await, 485_000);
+    assert_eq!(table.chips(2).await, 475_000);
+
+    // The ante does not count towards calling the big blind
+    table.call(0).await.unwrap();
+    assert_eq!(table.chips(0).await, 475_000);
+
+    table.fold(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.fold(2).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_eq!(table.chips(0).await, 500_000 + 2 * 5_000 + 10_000 + 20_000);
+}
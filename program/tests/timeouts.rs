@@ -0,0 +1,179 @@
+mod common;
+
+use common::{assert_poker_error, cards, not_a_card, TestTable, BUY_IN};
+use solana_poker::{GameStage, PokerError, SeatStatus};
+
+/// Past the 60 second action timeout
+const TIMED_OUT: i64 = 61;
+
+#[tokio::test]
+async fn stalled_bettor_folds_facing_a_bet_and_checks_otherwise() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    // Seat 0 faces the big blind but still has time
+    assert_poker_error(
+        table.timeout_action(1, false).await,
+        PokerError::ActionNotTimedOut,
+    );
+
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(1, false).await.unwrap();
+    let state = table.game_state().await;
+    assert!(state.seat(0).unwrap().has_folded);
+    assert_eq!(state.seat(0).unwrap().status, SeatStatus::Playing);
+    assert_eq!(state.current_player_turn, 1);
+
+    // Acting restarts the clock for the next player
+    table.call(1).await.unwrap();
+    assert_poker_error(
+        table.timeout_action(1, false).await,
+        PokerError::ActionNotTimedOut,
+    );
+
+    // The big blind owes nothing, so their option is checked
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(0, false).await.unwrap();
+    let state = table.game_state().await;
+    assert!(!state.seat(2).unwrap().has_folded);
+    assert_eq!(state.game_stage, GameStage::Flop);
+    assert_eq!(state.pot_total, 2 * state.config.big_blind);
+}
+
+#[tokio::test]
+async fn sat_out_players_are_skipped_until_they_return() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(2, true).await.unwrap();
+    assert_eq!(table.game_state().await.seat(0).unwrap().status, SeatStatus::SittingOut);
+
+    table.fold(1).await.unwrap();
+    table.showdown().await.unwrap();
+
+    // Nobody waits on seat 0's seed and the next hand is dealt without them
+    table.start_round().await.unwrap();
+    let state = table.game_state().await;
+    assert_eq!(state.dealt_in(), 2);
+    assert_eq!(state.seat(0).unwrap().hole_index, None);
+    assert_eq!(table.chips(0).await, BUY_IN);
+
+    // A hand nobody is waiting on cannot time out
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+    table.warp_clock(TIMED_OUT).await;
+    assert_poker_error(
+        table.timeout_action(0, false).await,
+        PokerError::ActionNotTimedOut,
+    );
+    table.showdown().await.unwrap();
+
+    // Topping up deals seat 0 back in
+    table.buy_in(0, BUY_IN).await.unwrap();
+    assert_eq!(table.game_state().await.seat(0).unwrap().status, SeatStatus::Playing);
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+    assert_eq!(table.game_state().await.dealt_in(), 3);
+}
+
+#[tokio::test]
+async fn stalled_encryption_pass_forfeits_the_blind() {
+    let mut table = TestTable::seated(3).await;
+    table.shuffle().await.unwrap();
+    table.start_round_only().await.unwrap();
+
+    // Seat 0 encrypts; the small blind in seat 1 never does
+    let state = table.game_state().await;
+    let (dealer, small_blind) = (state.dealer_index, state.config.small_blind);
+    table.encrypt_deck(0, state.deck.clone(), [7; 32]).await.unwrap();
+
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(2, false).await.unwrap();
+
+    // The hand is called off with the same button and the small blind split
+    let state = table.game_state().await;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.dealer_index, dealer);
+    assert_eq!(state.pot_total, 0);
+    assert_eq!(table.chips(0).await, BUY_IN + small_blind / 2);
+    assert_eq!(table.chips(1).await, BUY_IN - small_blind);
+    assert_eq!(table.chips(2).await, BUY_IN + small_blind / 2);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn withheld_showdown_key_forfeits_the_pot() {
+    let mut table = TestTable::seated(3).await;
+    table
+        .start_round_with(&cards("2h 7d Kh Kd As Ad 3c 8h Jd 4s 5c"))
+        .await
+        .unwrap();
+    while table.game_state().await.game_stage != GameStage::Showdown {
+        table.check_or_call_current().await.unwrap();
+    }
+
+    // Seat 2 sees the others' keys, would lose nothing by waiting, and stalls
+    for player in 0..2 {
+        let (encryption_key, decryption_key) = (
+            table.players[player].encryption_key,
+            table.players[player].decryption_key,
+        );
+        table
+            .reveal_key(player, encryption_key, decryption_key)
+            .await
+            .unwrap();
+    }
+    assert_poker_error(table.end_round().await, PokerError::KeysNotRevealed);
+
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(0, true).await.unwrap();
+
+    let state = table.game_state().await;
+    let big_blind = state.config.big_blind;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(state.seat(2).unwrap().status, SeatStatus::SittingOut);
+    assert_eq!(table.chips(0).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(2).await, BUY_IN - big_blind);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn withheld_key_on_a_disputed_board_forfeits_the_pot() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.check(1).await.unwrap();
+    table.check(2).await.unwrap();
+    table.fold(0).await.unwrap();
+
+    // Seat 0 breaks the turn after folding, then keeps the key that would
+    // show it
+    table.unmask_cards(0, vec![not_a_card()]).await.unwrap();
+    table.unmask_pending().await.unwrap();
+    assert!(table.game_state().await.board_disputed);
+    for player in 1..3 {
+        let (encryption_key, decryption_key) = (
+            table.players[player].encryption_key,
+            table.players[player].decryption_key,
+        );
+        table
+            .reveal_key(player, encryption_key, decryption_key)
+            .await
+            .unwrap();
+    }
+
+    table.warp_clock(TIMED_OUT).await;
+    table.timeout_action(1, false).await.unwrap();
+
+    let state = table.game_state().await;
+    let big_blind = state.config.big_blind;
+    assert_eq!(state.game_stage, GameStage::Waiting);
+    assert_eq!(table.chips(0).await, BUY_IN - big_blind);
+    assert_eq!(table.chips(1).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.chips(2).await, BUY_IN + big_blind / 2);
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+}
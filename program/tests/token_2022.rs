@@ -0,0 +1,125 @@
+mod common;
+
+use common::{assert_poker_error, table_config, TestTable, BUY_IN, STARTING_BALANCE, TABLE_ID};
+use solana_poker::{PokerError, PokerInstruction};
+use solana_program::{program_option::COption, pubkey::Pubkey};
+use solana_sdk::account::Account;
+use spl_token_2022::{
+    extension::{
+        default_account_state::DefaultAccountState, mint_close_authority::MintCloseAuthority,
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFeeConfig, ExtensionType,
+        StateWithExtensionsMut,
+    },
+    state::Mint,
+};
+
+/// A 6 decimal Token-2022 mint carrying `extension`, set up by `init`
+fn mint_with_extension(
+    extension: ExtensionType,
+    init: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+) -> Account {
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[extension]).unwrap();
+    let mut data = vec![0; space];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    init(&mut state);
+    state.base = Mint {
+        mint_authority: COption::Some(Pubkey::new_unique()),
+        decimals: 6,
+        is_initialized: true,
+        ..Mint::default()
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: spl_token_2022::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[tokio::test]
+async fn token_2022_tables_play_and_cash_out() {
+    let mut table = TestTable::with_token_program(2, spl_token_2022::id()).await;
+    table.initialize(2).await.unwrap();
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.buy_in(1, BUY_IN).await.unwrap();
+
+    let state = table.game_state().await;
+    assert_eq!(state.token_program, spl_token_2022::id());
+    let pot = table.pot;
+    assert_eq!(table.token_balance(pot).await, 2 * BUY_IN);
+
+    table.start_round().await.unwrap();
+    let seat = table.current_turn().await;
+    table.fold(seat).await.unwrap();
+    table.showdown().await.unwrap();
+
+    table.cash_out(0).await.unwrap();
+    table.cash_out(1).await.unwrap();
+    assert_eq!(table.token_balance(pot).await, 0);
+    let (player_0, player_1) = (table.players[0].token_account, table.players[1].token_account);
+    assert_eq!(
+        table.token_balance(player_0).await + table.token_balance(player_1).await,
+        2 * STARTING_BALANCE
+    );
+}
+
+#[tokio::test]
+async fn mints_with_pot_breaking_extensions_are_rejected() {
+    let mut table = TestTable::with_token_program(2, spl_token_2022::id()).await;
+
+    let unsupported = [
+        mint_with_extension(ExtensionType::TransferFeeConfig, |state| {
+            state.init_extension::<TransferFeeConfig>(true).unwrap();
+        }),
+        mint_with_extension(ExtensionType::DefaultAccountState, |state| {
+            state.init_extension::<DefaultAccountState>(true).unwrap();
+        }),
+        mint_with_extension(ExtensionType::PermanentDelegate, |state| {
+            state.init_extension::<PermanentDelegate>(true).unwrap();
+        }),
+    ];
+    for mint in unsupported {
+        let mint = table.add_account(mint);
+        let accounts = table.initialize_accounts(table.game_state, mint);
+        assert_poker_error(
+            table
+                .send_instruction(
+                    PokerInstruction::InitializeGame {
+                        max_players: 2,
+                        table_id: TABLE_ID,
+                        config: table_config(),
+                    },
+                    accounts,
+                    None,
+                )
+                .await,
+            PokerError::UnsupportedMint,
+        );
+    }
+
+    // Extensions that leave transfers alone are fine
+    let mint = table.add_account(mint_with_extension(
+        ExtensionType::MintCloseAuthority,
+        |state| {
+            state.init_extension::<MintCloseAuthority>(true).unwrap();
+        },
+    ));
+    let accounts = table.initialize_accounts(table.game_state, mint);
+    table
+        .send_instruction(
+            PokerInstruction::InitializeGame {
+                max_players: 2,
+                table_id: TABLE_ID,
+                config: table_config(),
+            },
+            accounts,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(table.game_state().await.mint, mint);
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::{assert_poker_error, TestTable};
+use solana_poker::PokerError;
+
+#[tokio::test]
+async fn actions_are_rejected_before_the_hand_starts() {
+    let mut table = TestTable::seated(3).await;
+
+    assert_poker_error(table.call(0).await, PokerError::NoActiveBettingRound);
+    assert_poker_error(table.fold(0).await, PokerError::NoActiveBettingRound);
+}
+
+#[tokio::test]
+async fn out_of_turn_actions_are_rejected() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    // Seat 0 is first to act after the blinds
+    assert_poker_error(table.call(2).await, PokerError::NotYourTurn);
+    assert_poker_error(table.raise(1, 50_000).await, PokerError::NotYourTurn);
+    assert_poker_error(table.fold(1).await, PokerError::NotYourTurn);
+
+    table.call(0).await.unwrap();
+    assert_eq!(table.game_state().await.current_player_turn, 1);
+}
+
+#[tokio::test]
+async fn folded_players_cannot_act_and_are_skipped() {
+    let mut table = TestTable::seated(3).await;
+    table.start_round().await.unwrap();
+
+    table.fold(0).await.unwrap();
+    assert_poker_error(table.call(0).await, PokerError::PlayerFolded);
+
+    table.call(1).await.unwrap();
+    table.raise(2, 50_000).await.unwrap();
+
+    // Action skips the folded seat 0 and returns to seat 1
+    assert_eq!(table.game_state().await.current_player_turn, 1);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{assert_poker_error, TestTable, BUY_IN};
+use solana_poker::PokerError;
+
+/// Check the vault holds exactly the chips the ledger says are in play
+async fn assert_vault_backs_the_ledger(table: &mut TestTable) {
+    let chips_in_play = table.game_state().await.chips_in_play().unwrap();
+    assert_eq!(table.token_balance(table.pot).await, chips_in_play);
+}
+
+#[tokio::test]
+async fn vault_backs_every_chip_through_a_hand() {
+    let mut table = TestTable::seated(3).await;
+    assert_vault_backs_the_ledger(&mut table).await;
+
+    // Betting only moves chips within the ledger
+    table.start_round().await.unwrap();
+    table.call(0).await.unwrap();
+    table.call(1).await.unwrap();
+    table.check(2).await.unwrap();
+    assert_vault_backs_the_ledger(&mut table).await;
+    assert_eq!(table.token_balance(table.pot).await, 3 * BUY_IN);
+
+    table.fold(1).await.unwrap();
+    table.fold(2).await.unwrap();
+    table.showdown().await.unwrap();
+    assert_vault_backs_the_ledger(&mut table).await;
+
+    table.cash_out(0).await.unwrap();
+    assert_vault_backs_the_ledger(&mut table).await;
+}
+
+#[tokio::test]
+async fn donations_to_the_vault_do_not_block_cashing_out() {
+    let mut table = TestTable::seated(2).await;
+
+    // Anyone can send tokens to the vault; they back no chips
+    table.set_token_balance(table.pot, 2 * BUY_IN + 1).await;
+    table.buy_in(0, BUY_IN).await.unwrap();
+    table.cash_out(0).await.unwrap();
+    table.cash_out(1).await.unwrap();
+    assert_eq!(table.chips(0).await, 0);
+    assert_eq!(table.token_balance(table.pot).await, 1);
+}
+
+#[tokio::test]
+async fn a_vault_short_of_the_ledger_is_rejected() {
+    let mut table = TestTable::seated(2).await;
+
+    // Chips cannot be paid out of a vault that is short
+    table.set_token_balance(table.pot, 2 * BUY_IN - 1).await;
+    assert_poker_error(table.cash_out(1).await, PokerError::VaultBalanceMismatch);
+
+    table.set_token_balance(table.pot, 2 * BUY_IN).await;
+    table.cash_out(1).await.unwrap();
+    assert_vault_backs_the_ledger(&mut table).await;
+}